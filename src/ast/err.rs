@@ -22,6 +22,14 @@ pub enum ASTError<'a> {
         help: &'a str,
         error: String,
     },
+    InvalidPickCount {
+        #[source_code]
+        src: String,
+        #[label("Expected a positive integer number of choices")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
     UnexpectedExpr {
         #[source_code]
         src: String,
@@ -97,7 +105,23 @@ pub enum ASTError<'a> {
     MissingWhileOrIF {
         #[source_code]
         src: String,
-        #[label("Missing the `WHILE` or `IF` Condition Declaration above the `]`")]
+        #[label("Missing the `WHILE`, `IF` or `REPEAT` Declaration above the `]`")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    MissingElseBlock {
+        #[source_code]
+        src: String,
+        #[label("Expected `] [` or `ELSE [` to open the second block of `IFELSE`")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    UnexpectedElse {
+        #[source_code]
+        src: String,
+        #[label("An else block can only follow the first block of `IF` or `IFELSE`")]
         bad_bit: SourceSpan,
         help: &'a str,
         error: String,
@@ -105,7 +129,17 @@ pub enum ASTError<'a> {
     DeclWrongPosition {
         #[source_code]
         src: String,
-        #[label("Function declaration cannot be placed inside the `IF` or `WHILE` statement")]
+        #[label(
+            "Function declaration cannot be placed inside the `IF`, `WHILE` or `REPEAT` statement"
+        )]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    OutputOutsideFunction {
+        #[source_code]
+        src: String,
+        #[label("`OUTPUT` and `STOP` can only be used inside a `TO` declaration")]
         bad_bit: SourceSpan,
         help: &'a str,
         error: String,
@@ -141,24 +175,33 @@ pub fn match_err<'a>(
             help: "Try using a valid number",
             error: format!("Invalid number (Ln {line}, Col {})", start + 1),
         },
+        "InvalidPickCount" => ASTError::InvalidPickCount {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Write the number of choices as a number, e.g. `PICK \"3 :A :B :C`",
+            error: format!("Invalid number of choices (Ln {line}, Col {})", start + 1),
+        },
         "UnexpectedExpr" => ASTError::UnexpectedExpr {
             src,
             bad_bit: (start, len).into(),
             help: "If it is a variable, the format is `:{ variable_name }`. \nIf it is a number, \
             the format is `\"{ number }`. \nIf it is a system variable, only `XCOR`, \
-            `YCOR`, `COLOR`, `HEADING` are allowed.",
+            `YCOR`, `COLOR`, `FILLCOLOR`, `PENSIZE`, `HEADING`, `REPCOUNT` are allowed. \nIf it is a built-in function, \
+            choose from `SIN`, `COS`, `TAN`, `ARCTAN`, `SQRT`, `ABS`, `ROUND`, `INT`, `POWER`, \
+            `MOD`, `RANDOM`, `RANDOMFLOAT`, `PICK` or `TOWARDS`.",
             error: format!("Unexpected Expression (Ln {line}, Col {})", start + 1),
         },
         "MissingLeftBracket" => ASTError::MissingLeftBracket {
             src,
             bad_bit: (start, len).into(),
-            help: "Add a left curly brace `[` to end of the `IF` or `WHILE` statement.",
+            help: "Add a left curly brace `[` to end of the `IF`, `WHILE` or `REPEAT` statement.",
             error: format!("Missing left curly brace (Ln {line}, Col {})", start + 1),
         },
         "MissingRightBracket" => ASTError::MissingRightBracket {
             src,
             bad_bit: (start, len).into(),
-            help: "Add a right curly brace `]` in a new line to end the `IF` or `WHILE` statement.",
+            help: "Add a right curly brace `]` in a new line to end the `IF`, `WHILE` or `REPEAT` \
+            statement.",
             error: format!("Missing right curly brace (Ln {line}, Col {})", start + 1),
         },
         "MissingOperand" => ASTError::MissingOperand {
@@ -200,15 +243,40 @@ pub fn match_err<'a>(
         "MissingWhileOrIf" => ASTError::MissingWhileOrIF {
             src,
             bad_bit: (start, len).into(),
-            help: "Add `IF` or `WHILE` to start the condition declaration.",
-            error: format!("Missing `IF` or `WHILE` (Ln {line}, Col {})", start + 1),
+            help: "Add `IF`, `WHILE` or `REPEAT` to start the block declaration.",
+            error: format!(
+                "Missing `IF`, `WHILE` or `REPEAT` (Ln {line}, Col {})",
+                start + 1
+            ),
+        },
+        "MissingElseBlock" => ASTError::MissingElseBlock {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Close the first block of `IFELSE` with `] [` and add the second block.",
+            error: format!("Missing else block (Ln {line}, Col {})", start + 1),
+        },
+        "UnexpectedElse" => ASTError::UnexpectedElse {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Place `ELSE [` directly after the `]` of an `IF` statement, or use `] [` \
+            to end the first block of an `IFELSE` statement.",
+            error: format!("Unexpected else block (Ln {line}, Col {})", start + 1),
         },
         "DeclWrongPosition" => ASTError::DeclWrongPosition {
             src,
             bad_bit: (start, len).into(),
-            help: "Move the function declaration outside the `IF` or `WHILE` statement.",
+            help: "Move the function declaration outside the `IF`, `WHILE` or `REPEAT` statement.",
             error: format!("Wrong Position of Function Declaration (Ln {line}, Col {})", start + 1),
         },
+        "OutputOutsideFunction" => ASTError::OutputOutsideFunction {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Move the statement into a function declaration between `TO` and `END`.",
+            error: format!(
+                "Return statement outside function (Ln {line}, Col {})",
+                start + 1
+            ),
+        },
         "RepeatFunctionName" => ASTError::RepeatFunctionName {
             src,
             bad_bit: (start, len).into(),
@@ -241,22 +309,44 @@ pub fn check_decl_name_err(input: &DeclName) -> Option<(String, usize, usize)> {
 
 pub fn check_stmt_err(input: &Stmt) -> Option<(String, usize, usize)> {
     match input {
-        Stmt::If(expr, ..) | Stmt::While(expr, ..) => check_expr_err(expr.as_ref()),
+        Stmt::If(expr, ..)
+        | Stmt::IfElse(expr, ..)
+        | Stmt::While(expr, ..)
+        | Stmt::Repeat(expr, ..) => check_expr_err(expr.as_ref()),
         Stmt::Make(expr1, expr2, ..) | Stmt::AddAssign(expr1, expr2, ..) => {
             if let Some(temp) = check_assign_err(expr1.as_ref()) {
                 return Some(temp);
             }
             check_expr_err(expr2.as_ref())
         }
+        Stmt::Local(assign, ..) => check_assign_err(assign.as_ref()),
+        Stmt::SetPenRgb(red, green, blue, ..) | Stmt::SetBackgroundRgb(red, green, blue, ..) => {
+            [red, green, blue]
+                .iter()
+                .find_map(|expr| check_expr_err(expr.as_ref()))
+        }
+        Stmt::Arc(a, b, ..) | Stmt::SetPos(a, b, ..) => {
+            [a, b].iter().find_map(|expr| check_expr_err(expr.as_ref()))
+        }
+        Stmt::SetPalette(index, red, green, blue, ..) => [index, red, green, blue]
+            .iter()
+            .find_map(|expr| check_expr_err(expr.as_ref())),
         Stmt::Forward(expr, ..)
         | Stmt::Back(expr, ..)
         | Stmt::Left(expr, ..)
         | Stmt::Right(expr, ..)
         | Stmt::SetPenColor(expr, ..)
+        | Stmt::SetPenSize(expr, ..)
+        | Stmt::SetFillColor(expr, ..)
+        | Stmt::SetBackground(expr, ..)
+        | Stmt::SetLabelHeight(expr, ..)
         | Stmt::Turn(expr, ..)
+        | Stmt::Circle(expr, ..)
         | Stmt::SetHeading(expr, ..)
         | Stmt::SetX(expr, ..)
-        | Stmt::SetY(expr, ..) => check_expr_err(expr.as_ref()),
+        | Stmt::SetY(expr, ..)
+        | Stmt::Rerandom(expr, ..)
+        | Stmt::Output(expr, ..) => check_expr_err(expr.as_ref()),
         Stmt::Func(name, assign, ..) => {
             if let Some(temp) = check_decl_name_err(name.as_ref()) {
                 return Some(temp);
@@ -299,6 +389,19 @@ pub fn check_expr_err(input: &Expr) -> Option<(String, usize, usize)> {
                 _ => None,
             }
         }
+        Expr::Power(a, b, ..) | Expr::Mod(a, b, ..) | Expr::Towards(a, b, ..) => {
+            check_expr_err(a.as_ref()).or_else(|| check_expr_err(b.as_ref()))
+        }
+        Expr::Sin(a, ..)
+        | Expr::Cos(a, ..)
+        | Expr::Tan(a, ..)
+        | Expr::ArcTan(a, ..)
+        | Expr::Sqrt(a, ..)
+        | Expr::Abs(a, ..)
+        | Expr::Round(a, ..)
+        | Expr::Int(a, ..)
+        | Expr::Random(a, ..) => check_expr_err(a.as_ref()),
+        Expr::Call(_, args, ..) | Expr::Pick(args, ..) => args.iter().find_map(check_expr_err),
         Expr::Error(s, end, len) => Some((s.to_string(), *end, *len)),
         _ => None,
     }
@@ -1,9 +1,14 @@
-use crate::ast::{structs::Expr, support::check_name};
+use crate::ast::{
+    structs::{Expr, Function},
+    support::check_name,
+};
 use nom::{
     branch::alt,
     bytes::complete::{is_not, tag, take_while1},
-    character::complete::{char, space0},
-    sequence::delimited,
+    character::complete::{char, none_of, space0},
+    combinator::not,
+    error::{Error, ErrorKind},
+    sequence::{delimited, terminated},
     IResult,
 };
 
@@ -67,13 +72,13 @@ fn parse_var(input: &str) -> IResult<&str, Expr> {
     }
 }
 
-fn parse_add(input: &str) -> IResult<&str, Expr> {
+fn parse_add<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     let (temp, _) = delimited(space0, char('+'), space0)(input)?;
-    let (temp, left) = parse_expr(temp).unwrap_or((
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
-    let (temp, right) = parse_expr(temp).unwrap_or((
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
@@ -88,13 +93,13 @@ fn parse_add(input: &str) -> IResult<&str, Expr> {
     ))
 }
 
-fn parse_sub(input: &str) -> IResult<&str, Expr> {
+fn parse_sub<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     let (temp, _) = delimited(space0, char('-'), space0)(input)?;
-    let (temp, left) = parse_expr(temp).unwrap_or((
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
-    let (temp, right) = parse_expr(temp).unwrap_or((
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
@@ -109,13 +114,13 @@ fn parse_sub(input: &str) -> IResult<&str, Expr> {
     ))
 }
 
-fn parse_mul(input: &str) -> IResult<&str, Expr> {
+fn parse_mul<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     let (temp, _) = delimited(space0, char('*'), space0)(input)?;
-    let (temp, left) = parse_expr(temp).unwrap_or((
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
-    let (temp, right) = parse_expr(temp).unwrap_or((
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
@@ -130,13 +135,13 @@ fn parse_mul(input: &str) -> IResult<&str, Expr> {
     ))
 }
 
-fn parse_div(input: &str) -> IResult<&str, Expr> {
+fn parse_div<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     let (temp, _) = delimited(space0, char('/'), space0)(input)?;
-    let (temp, left) = parse_expr(temp).unwrap_or((
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
-    let (temp, right) = parse_expr(temp).unwrap_or((
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
@@ -151,13 +156,13 @@ fn parse_div(input: &str) -> IResult<&str, Expr> {
     ))
 }
 
-fn parse_eq(input: &str) -> IResult<&str, Expr> {
+fn parse_eq<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     let (temp, _) = delimited(space0, tag("EQ"), space0)(input)?;
-    let (temp, left) = parse_expr(temp).unwrap_or((
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
-    let (temp, right) = parse_expr(temp).unwrap_or((
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
@@ -172,13 +177,13 @@ fn parse_eq(input: &str) -> IResult<&str, Expr> {
     ))
 }
 
-fn parse_ne(input: &str) -> IResult<&str, Expr> {
+fn parse_ne<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     let (temp, _) = delimited(space0, tag("NE"), space0)(input)?;
-    let (temp, left) = parse_expr(temp).unwrap_or((
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
-    let (temp, right) = parse_expr(temp).unwrap_or((
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
@@ -193,13 +198,13 @@ fn parse_ne(input: &str) -> IResult<&str, Expr> {
     ))
 }
 
-fn parse_lt(input: &str) -> IResult<&str, Expr> {
+fn parse_lt<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     let (temp, _) = delimited(space0, tag("LT"), space0)(input)?;
-    let (temp, left) = parse_expr(temp).unwrap_or((
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
-    let (temp, right) = parse_expr(temp).unwrap_or((
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
@@ -214,13 +219,13 @@ fn parse_lt(input: &str) -> IResult<&str, Expr> {
     ))
 }
 
-fn parse_gt(input: &str) -> IResult<&str, Expr> {
+fn parse_gt<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     let (temp, _) = delimited(space0, tag("GT"), space0)(input)?;
-    let (temp, left) = parse_expr(temp).unwrap_or((
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
-    let (temp, right) = parse_expr(temp).unwrap_or((
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
@@ -235,13 +240,13 @@ fn parse_gt(input: &str) -> IResult<&str, Expr> {
     ))
 }
 
-fn parse_and(input: &str) -> IResult<&str, Expr> {
+fn parse_and<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     let (temp, _) = delimited(space0, tag("AND"), space0)(input)?;
-    let (temp, left) = parse_expr(temp).unwrap_or((
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
-    let (temp, right) = parse_expr(temp).unwrap_or((
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
@@ -256,13 +261,13 @@ fn parse_and(input: &str) -> IResult<&str, Expr> {
     ))
 }
 
-fn parse_or(input: &str) -> IResult<&str, Expr> {
+fn parse_or<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     let (temp, _) = delimited(space0, tag("OR"), space0)(input)?;
-    let (temp, left) = parse_expr(temp).unwrap_or((
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
-    let (temp, right) = parse_expr(temp).unwrap_or((
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 0),
     ));
@@ -297,6 +302,165 @@ fn parse_color(input: &str) -> IResult<&str, Expr> {
     Ok((temp, Expr::Color(temp.len(), input.len() - temp.len())))
 }
 
+fn parse_fillcolor(input: &str) -> IResult<&str, Expr> {
+    let (temp, _) = delimited(space0, tag("FILLCOLOR"), space0)(input)?;
+    Ok((temp, Expr::FillColor(temp.len(), input.len() - temp.len())))
+}
+
+fn parse_pensize(input: &str) -> IResult<&str, Expr> {
+    let (temp, _) = delimited(space0, tag("PENSIZE"), space0)(input)?;
+    Ok((temp, Expr::PenSize(temp.len(), input.len() - temp.len())))
+}
+
+fn parse_repcount(input: &str) -> IResult<&str, Expr> {
+    let (temp, _) = delimited(space0, tag("REPCOUNT"), space0)(input)?;
+    Ok((temp, Expr::RepCount(temp.len(), input.len() - temp.len())))
+}
+
+fn parse_randomfloat(input: &str) -> IResult<&str, Expr> {
+    let (temp, _) = delimited(space0, tag("RANDOMFLOAT"), space0)(input)?;
+    Ok((
+        temp,
+        Expr::RandomFloat(temp.len(), input.len() - temp.len()),
+    ))
+}
+
+fn parse_pick<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
+    let (mut temp, _) =
+        delimited(space0, terminated(tag("PICK"), not(none_of(" ["))), space0)(input)?;
+
+    let count = match parse_expr(temp, func) {
+        Ok((str, Expr::Float(num, ..))) if num >= 1.0 && num.fract() == 0.0 => {
+            temp = str;
+            num as usize
+        }
+        Ok((str, _)) => {
+            return Ok((
+                str,
+                Expr::Error(
+                    "InvalidPickCount".to_string(),
+                    str.len(),
+                    temp.len() - str.len(),
+                ),
+            ))
+        }
+        Err(_) => {
+            return Ok((
+                temp,
+                Expr::Error("InvalidPickCount".to_string(), temp.len(), 0),
+            ))
+        }
+    };
+
+    let mut args = vec![];
+    for _ in 0..count {
+        let (str, expr) = parse_expr(temp, func).unwrap_or((
+            temp,
+            Expr::Error("MissingOperand".to_string(), temp.len(), 0),
+        ));
+        args.push(expr);
+        temp = str;
+    }
+
+    Ok((temp, Expr::Pick(args, temp.len(), input.len() - temp.len())))
+}
+
+fn parse_unary<'a>(
+    input: &'a str,
+    func: &Function,
+    name: &str,
+    expr: fn(Box<Expr>, usize, usize) -> Expr,
+) -> IResult<&'a str, Expr> {
+    let (temp, _) = delimited(space0, terminated(tag(name), not(none_of(" ["))), space0)(input)?;
+    let (temp, value) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 0),
+    ));
+    Ok((
+        temp,
+        expr(Box::new(value), temp.len(), input.len() - temp.len()),
+    ))
+}
+
+fn parse_binary<'a>(
+    input: &'a str,
+    func: &Function,
+    name: &str,
+    expr: fn(Box<Expr>, Box<Expr>, usize, usize) -> Expr,
+) -> IResult<&'a str, Expr> {
+    let (temp, _) = delimited(space0, terminated(tag(name), not(none_of(" ["))), space0)(input)?;
+    let (temp, left) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 0),
+    ));
+    let (temp, right) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 0),
+    ));
+    Ok((
+        temp,
+        expr(
+            Box::new(left),
+            Box::new(right),
+            temp.len(),
+            input.len() - temp.len(),
+        ),
+    ))
+}
+
+fn parse_math<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
+    alt((
+        |i| parse_unary(i, func, "SIN", Expr::Sin),
+        |i| parse_unary(i, func, "COS", Expr::Cos),
+        |i| parse_unary(i, func, "TAN", Expr::Tan),
+        |i| parse_unary(i, func, "ARCTAN", Expr::ArcTan),
+        |i| parse_unary(i, func, "SQRT", Expr::Sqrt),
+        |i| parse_unary(i, func, "ABS", Expr::Abs),
+        |i| parse_unary(i, func, "ROUND", Expr::Round),
+        |i| parse_unary(i, func, "INT", Expr::Int),
+        |i| parse_binary(i, func, "POWER", Expr::Power),
+        |i| parse_binary(i, func, "MOD", Expr::Mod),
+        |i| parse_binary(i, func, "TOWARDS", Expr::Towards),
+        |i| parse_unary(i, func, "RANDOM", Expr::Random),
+        |i| parse_pick(i, func),
+    ))(input)
+}
+
+fn parse_query(input: &str) -> IResult<&str, Expr> {
+    alt((
+        parse_xcor,
+        parse_ycor,
+        parse_heading,
+        parse_color,
+        parse_pensize,
+        parse_fillcolor,
+        parse_repcount,
+        parse_randomfloat,
+    ))(input)
+}
+
+fn parse_call<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
+    let (mut temp, name) = delimited(space0, is_not(" ["), space0)(input)?;
+    let arity = func
+        .get_args_value(name)
+        .ok_or(nom::Err::Error(Error::new(input, ErrorKind::Tag)))?;
+
+    let mut args = vec![];
+    for _ in 0..arity {
+        let (str, expr) = parse_expr(temp, func).unwrap_or((
+            temp,
+            Expr::Error("MissingOperand".to_string(), temp.len(), 0),
+        ));
+        args.push(expr);
+        temp = str;
+    }
+
+    Ok((
+        temp,
+        Expr::Call(name.to_string(), args, temp.len(), input.len() - temp.len()),
+    ))
+}
+
 fn parts_err(input: &str) -> IResult<&str, Expr> {
     let (temp, _) = delimited(space0, is_not(" ["), space0)(input)?;
     Ok((
@@ -309,26 +473,25 @@ fn parts_err(input: &str) -> IResult<&str, Expr> {
     ))
 }
 
-pub fn parse_expr(input: &str) -> IResult<&str, Expr> {
+pub fn parse_expr<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Expr> {
     alt((
         parse_true,
         parse_false,
         parse_num,
         parse_var,
-        parse_add,
-        parse_sub,
-        parse_mul,
-        parse_div,
-        parse_eq,
-        parse_ne,
-        parse_lt,
-        parse_gt,
-        parse_and,
-        parse_or,
-        parse_xcor,
-        parse_ycor,
-        parse_heading,
-        parse_color,
+        |i| parse_add(i, func),
+        |i| parse_sub(i, func),
+        |i| parse_mul(i, func),
+        |i| parse_div(i, func),
+        |i| parse_eq(i, func),
+        |i| parse_ne(i, func),
+        |i| parse_lt(i, func),
+        |i| parse_gt(i, func),
+        |i| parse_and(i, func),
+        |i| parse_or(i, func),
+        parse_query,
+        |i| parse_math(i, func),
+        |i| parse_call(i, func),
         parts_err,
     ))(input)
 }
@@ -0,0 +1,38 @@
+use crate::ast::lib::first_word;
+
+const INDENT: &str = "    ";
+
+/// Reprints a program with one space between tokens, four spaces of indentation for each open
+/// block or procedure, and no repeated, leading or trailing blank lines. Comments are only
+/// re-indented.
+pub fn format_source(file: &[String]) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    let mut depth = 0_usize;
+
+    for sentence in file {
+        let str = sentence.trim();
+        if str.is_empty() {
+            if lines.last().is_some_and(|line| !line.is_empty()) {
+                lines.push(String::new());
+            }
+            continue;
+        }
+
+        if str.starts_with(']') || first_word(str) == "END" {
+            depth = depth.saturating_sub(1);
+        }
+        let line = match str.starts_with("//") {
+            true => str.to_string(),
+            false => str.split_whitespace().collect::<Vec<_>>().join(" "),
+        };
+        lines.push(format!("{}{line}", INDENT.repeat(depth)));
+        if !line.starts_with("//") && (line.ends_with('[') || first_word(&line) == "TO") {
+            depth += 1;
+        }
+    }
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
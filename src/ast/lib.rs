@@ -8,12 +8,28 @@ use crate::ast::{
     structs::{Decl, DeclName, Function, Stmt},
 };
 
+pub(crate) fn first_word(sentence: &str) -> &str {
+    sentence.split_whitespace().next().unwrap_or_default()
+}
+
+fn is_block(sentence: &str) -> bool {
+    matches!(first_word(sentence), "IF" | "IFELSE" | "WHILE" | "REPEAT")
+}
+
+/// Whether the next line that is not blank starts an `ELSE` block.
+fn else_follows(rest: &[String]) -> bool {
+    rest.iter()
+        .map(|sentence| sentence.trim())
+        .find(|sentence| !sentence.is_empty())
+        .is_some_and(|sentence| first_word(sentence) == "ELSE")
+}
+
 fn struct_check(file: &[String]) -> Result<(), ASTError<'static>> {
     let mut if_while_list = vec![];
     let mut to = 0_usize;
     for (idx, sentence) in file.iter().enumerate() {
         let str = sentence.trim();
-        if str.starts_with("IF") || str.starts_with("WHILE") {
+        if is_block(str) {
             if !str.ends_with('[') {
                 return Err(match_err(
                     format!("{}  ", sentence.trim_end()),
@@ -35,7 +51,22 @@ fn struct_check(file: &[String]) -> Result<(), ASTError<'static>> {
                 ));
             }
             if_while_list.pop();
-        } else if str.starts_with("TO") {
+
+            if str.ends_with('[') {
+                if_while_list.push(idx);
+            }
+        } else if first_word(str) == "ELSE" {
+            if !str.ends_with('[') {
+                return Err(match_err(
+                    format!("{}  ", sentence.trim_end()),
+                    idx + 1,
+                    "MissingLeftBracket".to_string(),
+                    0,
+                    1,
+                ));
+            }
+            if_while_list.push(idx);
+        } else if first_word(str) == "TO" {
             if to != 0 {
                 return Err(match_err(
                     format!("{}\n ", file[to - 1]),
@@ -54,7 +85,7 @@ fn struct_check(file: &[String]) -> Result<(), ASTError<'static>> {
                 ));
             }
             to = idx + 1;
-        } else if str.starts_with("END") {
+        } else if first_word(str) == "END" {
             if to != 0 && !if_while_list.is_empty() {
                 return Err(match_err(
                     format!("{}\n ", file[if_while_list.pop().unwrap()]),
@@ -110,8 +141,17 @@ pub fn parse_ast(file: &[String]) -> Result<Function, ASTError<'static>> {
     struct_check(file)?;
 
     let mut res = Function::new();
+    let mut decls = Function::new();
+
+    for sentence in file.iter().filter(|x| first_word(x) == "TO") {
+        if let Ok((_, decl)) = parse_decl(sentence) {
+            if let DeclName::String(name, ..) = *decl.name {
+                decls.insert(name, decl.var, vec![]);
+            }
+        }
+    }
 
-    let mut record: VecDeque<Stmt> = VecDeque::new();
+    let mut record: VecDeque<(Stmt, bool)> = VecDeque::new();
     let mut local: VecDeque<Vec<Stmt>> = VecDeque::new();
 
     let mut func_record: Option<Decl> = None;
@@ -141,7 +181,7 @@ pub fn parse_ast(file: &[String]) -> Result<Function, ASTError<'static>> {
             }
 
             let block = local.pop_front().expect("Expected a block in local");
-            let stmt_to_modify = record
+            let (stmt_to_modify, in_else) = record
                 .pop_front()
                 .expect("Expected a statement to modify in record");
 
@@ -160,9 +200,121 @@ pub fn parse_ast(file: &[String]) -> Result<Function, ASTError<'static>> {
                         .expect("Expected a front block in local")
                         .push(modified_stmt);
                 }
+                Stmt::Repeat(expr, _, line) => {
+                    let modified_stmt = Stmt::Repeat(expr, block, line);
+                    local
+                        .front_mut()
+                        .expect("Expected a front block in local")
+                        .push(modified_stmt);
+                }
+                Stmt::IfElse(expr, _, _, line) if !in_else && else_follows(&file[idx + 1..]) => {
+                    local
+                        .front_mut()
+                        .expect("Expected a front block in local")
+                        .push(Stmt::If(expr, block, line));
+                }
+                Stmt::IfElse(expr, then_block, _, line) => {
+                    if !in_else {
+                        return Err(match_err(
+                            sentence.to_string(),
+                            idx + 1,
+                            "MissingElseBlock".to_string(),
+                            0,
+                            sentence.len(),
+                        ));
+                    }
+
+                    let modified_stmt = Stmt::IfElse(expr, then_block, block, line);
+                    local
+                        .front_mut()
+                        .expect("Expected a front block in local")
+                        .push(modified_stmt);
+                }
                 _ => unreachable!(),
             }
-        } else if trim_sentence.starts_with("TO") {
+        } else if trim_sentence.starts_with(']') {
+            if local.len() == 1 {
+                return Err(match_err(
+                    sentence.to_string(),
+                    idx + 1,
+                    "MissingWhileOrIf".to_string(),
+                    0,
+                    1,
+                ));
+            }
+
+            let rest = trim_sentence.trim_start_matches(']').trim();
+            let has_else = first_word(rest) == "ELSE";
+            let opening = match has_else {
+                true => rest.trim_start_matches("ELSE").trim(),
+                false => rest,
+            };
+            if opening != "[" {
+                return Err(match_err(
+                    sentence.to_string(),
+                    idx + 1,
+                    "UnexpectedExtraOperand".to_string(),
+                    0,
+                    sentence.trim_start().len() - 1,
+                ));
+            }
+
+            let block = local.pop_front().expect("Expected a block in local");
+            match (
+                record
+                    .pop_front()
+                    .expect("Expected a statement to modify in record"),
+                has_else,
+            ) {
+                ((Stmt::IfElse(expr, _, _, line), false), _)
+                | ((Stmt::If(expr, _, line), false), true) => {
+                    record.push_front((Stmt::IfElse(expr, block, vec![], line), true));
+                    local.push_front(Vec::new());
+                }
+                _ => {
+                    return Err(match_err(
+                        sentence.to_string(),
+                        idx + 1,
+                        "UnexpectedElse".to_string(),
+                        0,
+                        sentence.trim_start().len(),
+                    ))
+                }
+            }
+        } else if first_word(trim_sentence) == "ELSE" {
+            if trim_sentence.trim_start_matches("ELSE").trim() != "[" {
+                return Err(match_err(
+                    sentence.to_string(),
+                    idx + 1,
+                    "MissingLeftBracket".to_string(),
+                    0,
+                    1,
+                ));
+            }
+
+            match local
+                .front_mut()
+                .expect("Expected a front block in local")
+                .pop()
+            {
+                Some(Stmt::If(expr, block, line)) => {
+                    record.push_front((Stmt::IfElse(expr, block, vec![], line), true));
+                    local.push_front(Vec::new());
+                }
+                last => {
+                    if let Some(stmt) = last {
+                        local.front_mut().unwrap().push(stmt);
+                    }
+                    return Err(match_err(
+                        sentence.to_string(),
+                        idx + 1,
+                        "UnexpectedElse".to_string(),
+                        0,
+                        sentence.trim_start().len(),
+                    ));
+                }
+            }
+        } else if first_word(trim_sentence) == "TO" {
             let result = parse_decl(sentence).expect("Failed to parse declaration");
             if !result.0.is_empty() {
                 return Err(match_err(
@@ -213,11 +365,13 @@ pub fn parse_ast(file: &[String]) -> Result<Function, ASTError<'static>> {
                 );
             }
         } else {
-            let mut result = parse_stmt(sentence).expect("Expected a block in local");
+            let mut result = parse_stmt(sentence, &decls).expect("Expected a block in local");
 
             match &mut result.1 {
                 Stmt::If(.., line)
                 | Stmt::While(.., line)
+                | Stmt::Repeat(.., line)
+                | Stmt::IfElse(.., line)
                 | Stmt::Make(.., line)
                 | Stmt::PenUp(line)
                 | Stmt::PenDown(line)
@@ -226,16 +380,40 @@ pub fn parse_ast(file: &[String]) -> Result<Function, ASTError<'static>> {
                 | Stmt::Left(.., line)
                 | Stmt::Right(.., line)
                 | Stmt::SetPenColor(.., line)
+                | Stmt::SetPenSize(.., line)
+                | Stmt::PushTurtle(line)
+                | Stmt::PopTurtle(line)
+                | Stmt::SetBackground(.., line)
+                | Stmt::SetBackgroundRgb(.., line)
+                | Stmt::BeginFill(line)
+                | Stmt::EndFill(line)
+                | Stmt::SetFillColor(.., line)
+                | Stmt::Label(.., line)
+                | Stmt::SetLabelHeight(.., line)
+                | Stmt::SetPenRgb(.., line)
+                | Stmt::SetPalette(.., line)
                 | Stmt::Turn(.., line)
+                | Stmt::SetPos(.., line)
+                | Stmt::Home(line)
+                | Stmt::ClearScreen(line)
+                | Stmt::Window(line)
+                | Stmt::Wrap(line)
+                | Stmt::Fence(line)
+                | Stmt::Arc(.., line)
+                | Stmt::Circle(.., line)
                 | Stmt::SetHeading(.., line)
                 | Stmt::SetX(.., line)
                 | Stmt::SetY(.., line)
                 | Stmt::AddAssign(.., line)
-                | Stmt::Func(.., line) => *line = idx,
+                | Stmt::Func(.., line)
+                | Stmt::Output(.., line)
+                | Stmt::Local(.., line)
+                | Stmt::Rerandom(.., line)
+                | Stmt::Stop(line) => *line = idx,
                 _ => (),
             }
 
-            if trim_sentence.starts_with("IF") || trim_sentence.starts_with("WHILE") {
+            if is_block(trim_sentence) {
                 if result.0 != "[" {
                     return if result.0.is_empty() || !result.0.ends_with('[') {
                         Err(match_err(
@@ -260,7 +438,7 @@ pub fn parse_ast(file: &[String]) -> Result<Function, ASTError<'static>> {
                     return Err(match_err(sentence.to_string(), idx + 1, err, end, len));
                 }
 
-                record.push_front(result.1);
+                record.push_front((result.1, false));
                 local.push_front(Vec::new());
             } else {
                 if !result.0.is_empty() {
@@ -277,6 +455,16 @@ pub fn parse_ast(file: &[String]) -> Result<Function, ASTError<'static>> {
                     return Err(match_err(sentence.to_string(), idx + 1, err, end, len));
                 }
 
+                if let (Stmt::Output(..) | Stmt::Stop(..), None) = (&result.1, &func_record) {
+                    return Err(match_err(
+                        sentence.to_string(),
+                        idx + 1,
+                        "OutputOutsideFunction".to_string(),
+                        0,
+                        sentence.trim_start().len(),
+                    ));
+                }
+
                 local
                     .front_mut()
                     .expect("Expected a front block in local")
@@ -312,3 +500,181 @@ pub fn parse_ast(file: &[String]) -> Result<Function, ASTError<'static>> {
     }
     Ok(res)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(program: &str) -> Function {
+        let file = program.lines().map(String::from).collect::<Vec<_>>();
+        parse_ast(&file).expect("Failed to parse the program")
+    }
+
+    /// The name of the procedure `stmt` calls, if it is a call.
+    fn called(stmt: &Stmt) -> Option<&str> {
+        match stmt {
+            Stmt::Func(name, ..) => match name.as_ref() {
+                DeclName::String(name, ..) => Some(name),
+                DeclName::Error(..) => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn procedures_may_start_with_a_turtle_command() {
+        let ast = parse(
+            r#"TO HOMEWORK
+  FORWARD "10
+END
+HOMEWORK
+HOME
+"#,
+        );
+        let main = ast.get_main();
+
+        assert_eq!(called(&main[0]), Some("HOMEWORK"));
+        assert!(matches!(main[1], Stmt::Home(..)));
+    }
+
+    #[test]
+    fn procedures_may_start_with_arc_or_circle() {
+        let ast = parse(
+            r#"TO ARCH
+  ARC "180 "10
+END
+TO CIRCLES
+  CIRCLE "5
+END
+ARCH
+CIRCLES
+"#,
+        );
+        let main = ast.get_main();
+
+        assert_eq!(called(&main[0]), Some("ARCH"));
+        assert_eq!(called(&main[1]), Some("CIRCLES"));
+        assert!(matches!(
+            ast.get("ARCH").unwrap().stmt_list[0],
+            Stmt::Arc(..)
+        ));
+        assert!(matches!(
+            ast.get("CIRCLES").unwrap().stmt_list[0],
+            Stmt::Circle(..)
+        ));
+    }
+
+    #[test]
+    fn procedures_may_start_with_stop_or_output() {
+        let ast = parse(
+            r#"TO STOPWATCH
+  STOP
+END
+TO OUTPUTS
+  OUTPUT "1
+END
+STOPWATCH
+FORWARD OUTPUTS
+"#,
+        );
+
+        assert_eq!(called(&ast.get_main()[0]), Some("STOPWATCH"));
+        assert!(matches!(
+            ast.get("STOPWATCH").unwrap().stmt_list[0],
+            Stmt::Stop(..)
+        ));
+        assert!(matches!(
+            ast.get("OUTPUTS").unwrap().stmt_list[0],
+            Stmt::Output(..)
+        ));
+    }
+
+    #[test]
+    fn procedures_may_start_with_local() {
+        let ast = parse(
+            r#"TO LOCALIZE
+  LOCAL "x
+END
+LOCALIZE
+"#,
+        );
+
+        assert_eq!(called(&ast.get_main()[0]), Some("LOCALIZE"));
+        assert!(matches!(
+            ast.get("LOCALIZE").unwrap().stmt_list[0],
+            Stmt::Local(..)
+        ));
+    }
+
+    #[test]
+    fn procedures_may_start_with_a_boundary_mode() {
+        let ast = parse(
+            r#"TO WRAPPER
+  WRAP
+END
+TO FENCEPOST
+  FENCE
+END
+TO WINDOWSILL
+  WINDOW
+END
+WRAPPER
+FENCEPOST
+WINDOWSILL
+"#,
+        );
+        let main = ast.get_main();
+
+        assert_eq!(called(&main[0]), Some("WRAPPER"));
+        assert_eq!(called(&main[1]), Some("FENCEPOST"));
+        assert_eq!(called(&main[2]), Some("WINDOWSILL"));
+        assert!(matches!(
+            ast.get("WRAPPER").unwrap().stmt_list[0],
+            Stmt::Wrap(..)
+        ));
+    }
+
+    #[test]
+    fn ifelse_blocks_may_be_split_three_ways() {
+        for program in [
+            r#"IFELSE EQ "1 "1 [
+  FORWARD "1
+] [
+  BACK "1
+]
+"#,
+            r#"IF EQ "1 "1 [
+  FORWARD "1
+]
+ELSE [
+  BACK "1
+]
+"#,
+            r#"IFELSE EQ "1 "1 [
+  FORWARD "1
+]
+ELSE [
+  BACK "1
+]
+"#,
+            r#"IFELSE EQ "1 "1 [
+  FORWARD "1
+] ELSE [
+  BACK "1
+]
+"#,
+        ] {
+            let ast = parse(program);
+
+            assert!(
+                matches!(
+                    &ast.get_main()[..],
+                    [Stmt::IfElse(_, then, otherwise, _)]
+                        if matches!(then[..], [Stmt::Forward(..)])
+                            && matches!(otherwise[..], [Stmt::Back(..)])
+                ),
+                "{program}"
+            );
+        }
+    }
+}
@@ -1,9 +1,11 @@
+pub use format::format_source;
 pub use lib::parse_ast;
 
 mod assign;
 mod decl;
 mod err;
 mod expr;
+mod format;
 mod lib;
 mod stmt;
 pub mod structs;
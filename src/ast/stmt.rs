@@ -2,34 +2,65 @@ use crate::ast::{
     assign::parse_assign,
     decl::parse_decl_name,
     expr::parse_expr,
-    structs::{Expr, Stmt},
+    structs::{Assign, Expr, Function, Stmt},
 };
 use nom::{
-    branch::alt, bytes::complete::tag, character::complete::space0, sequence::delimited, IResult,
+    branch::alt,
+    bytes::complete::{is_not, tag, take_while_m_n},
+    character::complete::{char, none_of, space0},
+    combinator::not,
+    sequence::{delimited, preceded, terminated},
+    IResult,
 };
 
-fn parse_if(input: &str) -> IResult<&str, Stmt> {
-    let (temp, _) = delimited(space0, tag("IF"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+fn parse_if<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(space0, terminated(tag("IF"), not(none_of(" ["))), space0)(input)?;
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::If(Box::new(expr), vec![], 0)))
 }
 
-fn parse_while(input: &str) -> IResult<&str, Stmt> {
-    let (temp, _) = delimited(space0, tag("WHILE"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+fn parse_ifelse<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(
+        space0,
+        terminated(tag("IFELSE"), not(none_of(" ["))),
+        space0,
+    )(input)?;
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::IfElse(Box::new(expr), vec![], vec![], 0)))
+}
+
+fn parse_while<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(space0, terminated(tag("WHILE"), not(none_of(" ["))), space0)(input)?;
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::While(Box::new(expr), vec![], 0)))
 }
 
-fn parse_make(input: &str) -> IResult<&str, Stmt> {
+fn parse_repeat<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(
+        space0,
+        terminated(tag("REPEAT"), not(none_of(" ["))),
+        space0,
+    )(input)?;
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::Repeat(Box::new(expr), vec![], 0)))
+}
+
+fn parse_make<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("MAKE"), space0)(input)?;
     let (temp, assign) = parse_assign(temp)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
@@ -46,102 +77,364 @@ fn parse_pendown(input: &str) -> IResult<&str, Stmt> {
     Ok((temp, Stmt::PenDown(0)))
 }
 
-fn parse_forward(input: &str) -> IResult<&str, Stmt> {
+fn parse_forward<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("FORWARD"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::Forward(Box::new(expr), 0)))
 }
 
-fn parse_back(input: &str) -> IResult<&str, Stmt> {
+fn parse_back<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("BACK"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::Back(Box::new(expr), 0)))
 }
 
-fn parse_left(input: &str) -> IResult<&str, Stmt> {
+fn parse_left<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("LEFT"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::Left(Box::new(expr), 0)))
 }
 
-fn parse_right(input: &str) -> IResult<&str, Stmt> {
+fn parse_right<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("RIGHT"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::Right(Box::new(expr), 0)))
 }
 
-fn parse_setpencolor(input: &str) -> IResult<&str, Stmt> {
+fn parse_setpos<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(
+        space0,
+        terminated(alt((tag("SETPOS"), tag("SETXY"))), not(none_of(" ["))),
+        space0,
+    )(input)?;
+    let (temp, x) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    let (temp, y) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::SetPos(Box::new(x), Box::new(y), 0)))
+}
+
+fn parse_home(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(space0, terminated(tag("HOME"), not(none_of(" ["))), space0)(input)?;
+    Ok((temp, Stmt::Home(0)))
+}
+
+fn parse_clearscreen(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(
+        space0,
+        terminated(tag("CLEARSCREEN"), not(none_of(" ["))),
+        space0,
+    )(input)?;
+    Ok((temp, Stmt::ClearScreen(0)))
+}
+
+fn parse_window(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(
+        space0,
+        terminated(tag("WINDOW"), not(none_of(" ["))),
+        space0,
+    )(input)?;
+    Ok((temp, Stmt::Window(0)))
+}
+
+fn parse_wrap(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(space0, terminated(tag("WRAP"), not(none_of(" ["))), space0)(input)?;
+    Ok((temp, Stmt::Wrap(0)))
+}
+
+fn parse_fence(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(space0, terminated(tag("FENCE"), not(none_of(" ["))), space0)(input)?;
+    Ok((temp, Stmt::Fence(0)))
+}
+
+fn parse_arc<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(space0, terminated(tag("ARC"), not(none_of(" ["))), space0)(input)?;
+    let (temp, angle) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    let (temp, radius) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::Arc(Box::new(angle), Box::new(radius), 0)))
+}
+
+fn parse_circle<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(
+        space0,
+        terminated(tag("CIRCLE"), not(none_of(" ["))),
+        space0,
+    )(input)?;
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::Circle(Box::new(expr), 0)))
+}
+
+fn parse_hex_color(input: &str) -> IResult<&str, Stmt> {
+    let (temp, hex) = delimited(
+        space0,
+        preceded(
+            tag("\"#"),
+            take_while_m_n(6, 6, |c: char| c.is_ascii_hexdigit()),
+        ),
+        space0,
+    )(input)?;
+
+    let channel = |idx: usize| {
+        Box::new(Expr::Float(
+            u8::from_str_radix(&hex[idx..idx + 2], 16).unwrap_or_default() as f32,
+            temp.len(),
+            input.len() - temp.len(),
+        ))
+    };
+    Ok((temp, Stmt::SetPenRgb(channel(0), channel(2), channel(4), 0)))
+}
+
+fn parse_setpencolor<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("SETPENCOLOR"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    if let Ok(result) = parse_hex_color(temp) {
+        return Ok(result);
+    }
+
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::SetPenColor(Box::new(expr), 0)))
 }
 
-fn parse_turn(input: &str) -> IResult<&str, Stmt> {
+fn parse_setbackground<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(space0, tag("SETBACKGROUND"), space0)(input)?;
+    if let Ok((temp, Stmt::SetPenRgb(red, green, blue, _))) = parse_hex_color(temp) {
+        return Ok((temp, Stmt::SetBackgroundRgb(red, green, blue, 0)));
+    }
+
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    let Ok((temp, green)) = parse_expr(temp, func) else {
+        return Ok((temp, Stmt::SetBackground(Box::new(expr), 0)));
+    };
+    let (temp, blue) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((
+        temp,
+        Stmt::SetBackgroundRgb(Box::new(expr), Box::new(green), Box::new(blue), 0),
+    ))
+}
+
+fn parse_setpensize<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(space0, tag("SETPENSIZE"), space0)(input)?;
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::SetPenSize(Box::new(expr), 0)))
+}
+
+fn parse_pushturtle(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(space0, tag("PUSHTURTLE"), space0)(input)?;
+    Ok((temp, Stmt::PushTurtle(0)))
+}
+
+fn parse_popturtle(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(space0, tag("POPTURTLE"), space0)(input)?;
+    Ok((temp, Stmt::PopTurtle(0)))
+}
+
+fn parse_beginfill(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(space0, tag("BEGINFILL"), space0)(input)?;
+    Ok((temp, Stmt::BeginFill(0)))
+}
+
+fn parse_endfill(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(space0, tag("ENDFILL"), space0)(input)?;
+    Ok((temp, Stmt::EndFill(0)))
+}
+
+fn parse_setfillcolor<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(space0, tag("SETFILLCOLOR"), space0)(input)?;
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::SetFillColor(Box::new(expr), 0)))
+}
+
+fn parse_label(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(space0, tag("LABEL"), space0)(input)?;
+    let (temp, word) = delimited(char('"'), is_not(" "), space0)(temp)?;
+    Ok((temp, Stmt::Label(word.to_string(), 0)))
+}
+
+fn parse_setlabelheight<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(space0, tag("SETLABELHEIGHT"), space0)(input)?;
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::SetLabelHeight(Box::new(expr), 0)))
+}
+
+fn parse_setpenrgb<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(space0, tag("SETPENRGB"), space0)(input)?;
+    let (temp, red) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    let (temp, green) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    let (temp, blue) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((
+        temp,
+        Stmt::SetPenRgb(Box::new(red), Box::new(green), Box::new(blue), 0),
+    ))
+}
+
+fn parse_setpalette<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(space0, tag("SETPALETTE"), space0)(input)?;
+    let (temp, index) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    let (temp, red) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    let (temp, green) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    let (temp, blue) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((
+        temp,
+        Stmt::SetPalette(
+            Box::new(index),
+            Box::new(red),
+            Box::new(green),
+            Box::new(blue),
+            0,
+        ),
+    ))
+}
+
+fn parse_turn<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("TURN"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::Turn(Box::new(expr), 0)))
 }
 
-fn parse_setheading(input: &str) -> IResult<&str, Stmt> {
+fn parse_setheading<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("SETHEADING"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::SetHeading(Box::new(expr), 0)))
 }
 
-fn parse_setx(input: &str) -> IResult<&str, Stmt> {
+fn parse_setx<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("SETX"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::SetX(Box::new(expr), 0)))
 }
 
-fn parse_sety(input: &str) -> IResult<&str, Stmt> {
+fn parse_sety<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("SETY"), space0)(input)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::SetY(Box::new(expr), 0)))
 }
 
-fn parse_addassign(input: &str) -> IResult<&str, Stmt> {
+fn parse_addassign<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (temp, _) = delimited(space0, tag("ADDASSIGN"), space0)(input)?;
     let (temp, assign) = parse_assign(temp)?;
-    let (temp, expr) = parse_expr(temp).unwrap_or((
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
         temp,
         Expr::Error("MissingOperand".to_string(), temp.len(), 1),
     ));
     Ok((temp, Stmt::AddAssign(Box::new(assign), Box::new(expr), 0)))
 }
 
-fn parse_func(input: &str) -> IResult<&str, Stmt> {
+fn parse_output<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(
+        space0,
+        terminated(tag("OUTPUT"), not(none_of(" ["))),
+        space0,
+    )(input)?;
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::Output(Box::new(expr), 0)))
+}
+
+fn parse_local(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(space0, terminated(tag("LOCAL"), not(none_of(" ["))), space0)(input)?;
+    let (temp, assign) = parse_assign(temp).unwrap_or((
+        temp,
+        Assign::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::Local(Box::new(assign), 0)))
+}
+
+fn parse_rerandom<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    let (temp, _) = delimited(space0, tag("RERANDOM"), space0)(input)?;
+    let (temp, expr) = parse_expr(temp, func).unwrap_or((
+        temp,
+        Expr::Error("MissingOperand".to_string(), temp.len(), 1),
+    ));
+    Ok((temp, Stmt::Rerandom(Box::new(expr), 0)))
+}
+
+fn parse_stop(input: &str) -> IResult<&str, Stmt> {
+    let (temp, _) = delimited(space0, terminated(tag("STOP"), not(none_of(" ["))), space0)(input)?;
+    Ok((temp, Stmt::Stop(0)))
+}
+
+fn parse_func<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     let (mut temp, func_name) = parse_decl_name(input)?;
     let mut var = vec![];
 
-    while let Ok((str, expr)) = parse_expr(temp) {
+    while let Ok((str, expr)) = parse_expr(temp, func) {
         var.push(expr);
         temp = str;
     }
@@ -149,23 +442,75 @@ fn parse_func(input: &str) -> IResult<&str, Stmt> {
     Ok((temp, Stmt::Func(Box::new(func_name), var, 0)))
 }
 
-pub fn parse_stmt(input: &str) -> IResult<&str, Stmt> {
+fn parse_block<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    alt((
+        |i| parse_ifelse(i, func),
+        |i| parse_if(i, func),
+        |i| parse_while(i, func),
+        |i| parse_repeat(i, func),
+    ))(input)
+}
+
+fn parse_move<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    alt((
+        |i| parse_forward(i, func),
+        |i| parse_back(i, func),
+        |i| parse_left(i, func),
+        |i| parse_right(i, func),
+        |i| parse_turn(i, func),
+        |i| parse_setheading(i, func),
+        |i| parse_setpos(i, func),
+        |i| parse_setx(i, func),
+        |i| parse_sety(i, func),
+        |i| parse_arc(i, func),
+        |i| parse_circle(i, func),
+        parse_home,
+        parse_clearscreen,
+        parse_window,
+        parse_wrap,
+        parse_fence,
+    ))(input)
+}
+
+fn parse_pen<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
     alt((
-        parse_if,
-        parse_while,
-        parse_make,
         parse_penup,
         parse_pendown,
-        parse_forward,
-        parse_back,
-        parse_left,
-        parse_right,
-        parse_setpencolor,
-        parse_turn,
-        parse_setheading,
-        parse_setx,
-        parse_sety,
-        parse_addassign,
-        parse_func,
+        |i| parse_setpencolor(i, func),
+        |i| parse_setpenrgb(i, func),
+        |i| parse_setpalette(i, func),
+        |i| parse_setpensize(i, func),
+        |i| parse_setbackground(i, func),
+        parse_pushturtle,
+        parse_popturtle,
+    ))(input)
+}
+
+fn parse_fill<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    alt((parse_beginfill, parse_endfill, |i| {
+        parse_setfillcolor(i, func)
+    }))(input)
+}
+
+fn parse_label_stmt<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    alt((parse_label, |i| parse_setlabelheight(i, func)))(input)
+}
+
+fn parse_procedure<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    alt((parse_stop, parse_local, |i| parse_output(i, func)))(input)
+}
+
+pub fn parse_stmt<'a>(input: &'a str, func: &Function) -> IResult<&'a str, Stmt> {
+    alt((
+        |i| parse_block(i, func),
+        |i| parse_make(i, func),
+        |i| parse_pen(i, func),
+        |i| parse_fill(i, func),
+        |i| parse_label_stmt(i, func),
+        |i| parse_move(i, func),
+        |i| parse_addassign(i, func),
+        |i| parse_rerandom(i, func),
+        |i| parse_procedure(i, func),
+        |i| parse_func(i, func),
     ))(input)
 }
@@ -19,13 +19,33 @@ pub enum Expr {
     YCor(usize, usize),
     Heading(usize, usize),
     Color(usize, usize),
+    PenSize(usize, usize),
+    FillColor(usize, usize),
+    RepCount(usize, usize),
+    Call(String, Vec<Expr>, usize, usize),
+    Sin(Box<Expr>, usize, usize),
+    Cos(Box<Expr>, usize, usize),
+    Tan(Box<Expr>, usize, usize),
+    ArcTan(Box<Expr>, usize, usize),
+    Sqrt(Box<Expr>, usize, usize),
+    Abs(Box<Expr>, usize, usize),
+    Round(Box<Expr>, usize, usize),
+    Int(Box<Expr>, usize, usize),
+    Power(Box<Expr>, Box<Expr>, usize, usize),
+    Mod(Box<Expr>, Box<Expr>, usize, usize),
+    Random(Box<Expr>, usize, usize),
+    RandomFloat(usize, usize),
+    Pick(Vec<Expr>, usize, usize),
+    Towards(Box<Expr>, Box<Expr>, usize, usize),
     Error(String, usize, usize),
 }
 
 #[derive(Debug, PartialEq)]
 pub enum Stmt {
     If(Box<Expr>, Vec<Stmt>, usize),
+    IfElse(Box<Expr>, Vec<Stmt>, Vec<Stmt>, usize),
     While(Box<Expr>, Vec<Stmt>, usize),
+    Repeat(Box<Expr>, Vec<Stmt>, usize),
     Make(Box<Assign>, Box<Expr>, usize),
     PenUp(usize),
     PenDown(usize),
@@ -34,12 +54,36 @@ pub enum Stmt {
     Left(Box<Expr>, usize),
     Right(Box<Expr>, usize),
     SetPenColor(Box<Expr>, usize),
+    SetPenSize(Box<Expr>, usize),
+    SetPenRgb(Box<Expr>, Box<Expr>, Box<Expr>, usize),
+    PushTurtle(usize),
+    PopTurtle(usize),
+    SetBackground(Box<Expr>, usize),
+    SetBackgroundRgb(Box<Expr>, Box<Expr>, Box<Expr>, usize),
+    BeginFill(usize),
+    EndFill(usize),
+    SetFillColor(Box<Expr>, usize),
+    Label(String, usize),
+    SetLabelHeight(Box<Expr>, usize),
+    SetPalette(Box<Expr>, Box<Expr>, Box<Expr>, Box<Expr>, usize),
     Turn(Box<Expr>, usize),
+    Arc(Box<Expr>, Box<Expr>, usize),
+    Circle(Box<Expr>, usize),
     SetHeading(Box<Expr>, usize),
     SetX(Box<Expr>, usize),
     SetY(Box<Expr>, usize),
+    SetPos(Box<Expr>, Box<Expr>, usize),
+    Home(usize),
+    ClearScreen(usize),
+    Window(usize),
+    Wrap(usize),
+    Fence(usize),
     AddAssign(Box<Assign>, Box<Expr>, usize),
     Func(Box<DeclName>, Vec<Expr>, usize),
+    Output(Box<Expr>, usize),
+    Local(Box<Assign>, usize),
+    Rerandom(Box<Expr>, usize),
+    Stop(usize),
     Comments(String, usize),
 }
 
@@ -115,18 +159,6 @@ impl Function {
         set
     }
 
-    pub fn get_args(&self) -> Vec<String> {
-        let mut list = vec![];
-        for arg in self.map.values() {
-            for a in arg.args.iter() {
-                if let Assign::Var(name, ..) = &a {
-                    list.push(name.to_string());
-                }
-            }
-        }
-        list
-    }
-
     pub fn get_all(&self) -> &HashMap<String, FunctionType> {
         &self.map
     }
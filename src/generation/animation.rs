@@ -0,0 +1,251 @@
+use resvg::tiny_skia;
+use std::fs::File;
+use std::path::Path;
+use unsvg::Color;
+
+use crate::generation::display::{DisplayList, Shape, Step};
+use crate::generation::image::{get_end_coordinates, hex, quantize, text_element, Image};
+
+const CURSOR_COLOR: Color = Color {
+    red: 0,
+    green: 200,
+    blue: 0,
+};
+const CURSOR_SIZE: f32 = 8.0;
+/// How long the finished drawing stays on screen before the GIF starts again, in 1/100s.
+const FINAL_DELAY: u16 = 200;
+
+pub struct Animation {
+    pub fps: f32,
+    pub segments_per_frame: usize,
+}
+
+fn stroke(color: Color, width: f32) -> String {
+    match width == 1.0 {
+        true => format!("fill=\"none\" stroke=\"{}\"", hex(color)),
+        false => format!(
+            "fill=\"none\" stroke=\"{}\" stroke-width=\"{width}\"",
+            hex(color)
+        ),
+    }
+}
+
+/// Writes a shape as an SVG element. The animated SVG is written by hand, since usvg can
+/// neither write animations nor text.
+fn element(shape: &Shape) -> String {
+    match shape {
+        Shape::Line {
+            start,
+            end,
+            color,
+            width,
+        } => format!(
+            "<path {} d=\"M {} {} L {} {}\"/>",
+            stroke(*color, *width),
+            quantize(start.0),
+            quantize(start.1),
+            quantize(end.0),
+            quantize(end.1),
+        ),
+        Shape::Arc {
+            start,
+            end,
+            radius,
+            angle,
+            color,
+            width,
+            ..
+        } => {
+            let large = (angle.abs() > 180.0) as u8;
+            let sweep = (*angle > 0.0) as u8;
+            format!(
+                "<path {} d=\"M {} {} A {radius} {radius} 0 {large} {sweep} {} {}\"/>",
+                stroke(*color, *width),
+                quantize(start.0),
+                quantize(start.1),
+                quantize(end.0),
+                quantize(end.1),
+            )
+        }
+        Shape::Circle {
+            center,
+            radius,
+            color,
+            width,
+        } => format!(
+            "<circle {} cx=\"{}\" cy=\"{}\" r=\"{radius}\"/>",
+            stroke(*color, *width),
+            quantize(center.0),
+            quantize(center.1),
+        ),
+        Shape::Fill { points, color } => format!(
+            "<polygon fill=\"{}\" points=\"{}\"/>",
+            hex(*color),
+            points
+                .iter()
+                .map(|&(x, y)| format!("{},{}", quantize(x), quantize(y)))
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        Shape::Label {
+            text,
+            position,
+            direction,
+            height,
+            color,
+        } => text_element(text, *position, *direction, *height, *color),
+    }
+}
+
+fn cursor_points(position: (f32, f32), direction: f32) -> Vec<(f32, f32)> {
+    [0.0, 140.0, 220.0]
+        .into_iter()
+        .map(|angle| get_end_coordinates(position.0, position.1, direction + angle, CURSOR_SIZE))
+        .collect()
+}
+
+impl Animation {
+    fn steps<'a>(&self, display: &'a DisplayList) -> Result<&'a [Step], String> {
+        match display.steps.as_deref() {
+            Some(steps) if !steps.is_empty() => Ok(steps),
+            _ => Err("Nothing was recorded to animate".to_string()),
+        }
+    }
+
+    fn frame_of(&self, step: usize) -> usize {
+        step / self.segments_per_frame
+    }
+
+    fn time(&self, step: usize) -> f32 {
+        self.frame_of(step) as f32 / self.fps
+    }
+
+    pub fn save_gif<P: AsRef<Path>>(&self, display: &DisplayList, path: P) -> Result<(), String> {
+        let steps = self.steps(display)?;
+        let frames = self.frame_of(steps.len() - 1) + 1;
+        let delay = (100.0 / self.fps).round().clamp(1.0, u16::MAX as f32) as u16;
+
+        let history = display.history();
+        // The drawing so far without the cursor, and which shapes of the history it shows.
+        let mut drawing: Option<tiny_skia::Pixmap> = None;
+        let mut painted = vec![false; history.len()];
+        let mut encoder: Option<gif::Encoder<File>> = None;
+        for frame in 0..frames {
+            let step = ((frame + 1) * self.segments_per_frame).min(steps.len()) - 1;
+            let shown = history
+                .iter()
+                .map(|(_, drawn, erased)| {
+                    *drawn <= step && erased.is_none_or(|erased| erased > step)
+                })
+                .collect::<Vec<_>>();
+
+            // New shapes are painted over the last frame, unless the canvas was cleared or a
+            // shape goes beneath one already painted, such as a fill under its outline.
+            let last = painted.iter().rposition(|&painted| painted);
+            let first = (0..history.len()).find(|&i| shown[i] && !painted[i]);
+            let beneath = matches!((first, last), (Some(first), Some(last)) if first < last);
+            let cleared = painted
+                .iter()
+                .zip(&shown)
+                .any(|(&painted, &shown)| painted && !shown);
+            let canvas = match drawing.take() {
+                Some(mut canvas) if !cleared && !beneath => {
+                    let shapes = (0..history.len())
+                        .filter(|&i| shown[i] && !painted[i])
+                        .map(|i| history[i].0);
+                    Image::overlay(display.view, shapes)?.paint(&mut canvas)?;
+                    canvas
+                }
+                _ => Image::render(&display.frame(step))?.pixmap(1.0)?,
+            };
+            let mut pixmap = canvas.clone();
+            drawing = Some(canvas);
+            painted = shown;
+
+            let cursor = Shape::Fill {
+                points: cursor_points(steps[step].position, steps[step].direction),
+                color: CURSOR_COLOR,
+            };
+            Image::overlay(display.view, [&cursor])?.paint(&mut pixmap)?;
+            let (width, height) = (pixmap.width(), pixmap.height());
+            let (Ok(width), Ok(height)) = (u16::try_from(width), u16::try_from(height)) else {
+                return Err("Image is too large for a GIF".to_string());
+            };
+
+            let encoder = match &mut encoder {
+                Some(encoder) => encoder,
+                None => {
+                    let file = File::create(&path).map_err(|e| e.to_string())?;
+                    let mut new =
+                        gif::Encoder::new(file, width, height, &[]).map_err(|e| e.to_string())?;
+                    new.set_repeat(gif::Repeat::Infinite)
+                        .map_err(|e| e.to_string())?;
+                    encoder.insert(new)
+                }
+            };
+            let mut frame_data = gif::Frame::from_rgba_speed(width, height, pixmap.data_mut(), 10);
+            frame_data.delay = match frame + 1 == frames {
+                true => delay.saturating_add(FINAL_DELAY),
+                false => delay,
+            };
+            encoder
+                .write_frame(&frame_data)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    pub fn save_svg<P: AsRef<Path>>(&self, display: &DisplayList, path: P) -> Result<(), String> {
+        let steps = self.steps(display)?;
+        let (x, y, width, height) = display.view;
+        let mut svg = format!(
+            "<svg width=\"{width}\" height=\"{height}\" viewBox=\"{x} {y} {width} {height}\" \
+             xmlns=\"http://www.w3.org/2000/svg\">\n\
+             <rect x=\"{x}\" y=\"{y}\" width=\"{width}\" height=\"{height}\" fill=\"{}\"/>\n",
+            hex(display.background)
+        );
+
+        for (shape, shown, hidden) in display.history() {
+            svg.push_str(&format!(
+                "<g visibility=\"hidden\"><set attributeName=\"visibility\" to=\"visible\" \
+                 begin=\"{}s\" fill=\"freeze\"/>",
+                self.time(shown)
+            ));
+            if let Some(hidden) = hidden {
+                svg.push_str(&format!(
+                    "<set attributeName=\"visibility\" to=\"hidden\" begin=\"{}s\" \
+                     fill=\"freeze\"/>",
+                    self.time(hidden)
+                ));
+            }
+            svg.push_str(&element(shape));
+            svg.push_str("</g>\n");
+        }
+
+        let frames = self.frame_of(steps.len() - 1) + 1;
+        let mut positions = Vec::with_capacity(frames);
+        for frame in 0..frames {
+            let step = &steps[((frame + 1) * self.segments_per_frame).min(steps.len()) - 1];
+            let points = cursor_points(step.position, step.direction)
+                .iter()
+                .map(|(x, y)| format!("{x},{y}"))
+                .collect::<Vec<_>>();
+            positions.push(points.join(" "));
+        }
+        let key_times = (0..frames)
+            .map(|frame| (frame as f32 / frames as f32).to_string())
+            .collect::<Vec<_>>();
+        svg.push_str(&format!(
+            "<polygon fill=\"{}\" points=\"{}\"><animate attributeName=\"points\" \
+             calcMode=\"discrete\" dur=\"{}s\" fill=\"freeze\" values=\"{}\" keyTimes=\"{}\"/>\
+             </polygon>\n</svg>\n",
+            hex(CURSOR_COLOR),
+            positions[0],
+            frames as f32 / self.fps,
+            positions.join(";"),
+            key_times.join(";"),
+        ));
+
+        std::fs::write(path, svg).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,291 @@
+use unsvg::Color;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Shape {
+    Line {
+        start: (f32, f32),
+        end: (f32, f32),
+        color: Color,
+        width: f32,
+    },
+    Arc {
+        start: (f32, f32),
+        end: (f32, f32),
+        center: (f32, f32),
+        radius: f32,
+        angle: f32,
+        color: Color,
+        width: f32,
+    },
+    Circle {
+        center: (f32, f32),
+        radius: f32,
+        color: Color,
+        width: f32,
+    },
+    Fill {
+        points: Vec<(f32, f32)>,
+        color: Color,
+    },
+    Label {
+        text: String,
+        position: (f32, f32),
+        direction: f32,
+        height: f32,
+        color: Color,
+    },
+}
+
+/// Splits an arc into cubic Béziers of at most 90 degrees each, as the control points and end
+/// point of every curve.
+pub fn arc_curves(
+    (cx, cy): (f32, f32),
+    radius: f32,
+    from: f32,
+    angle: f32,
+) -> Vec<[(f32, f32); 3]> {
+    let steps = (angle.abs() / 90.0).ceil().max(1.0) as usize;
+    let sweep = (angle / steps as f32).to_radians();
+    let k = 4.0 / 3.0 * (sweep / 4.0).tan() * radius;
+    (0..steps)
+        .map(|step| {
+            let a0 = from.to_radians() + sweep * step as f32;
+            let a1 = a0 + sweep;
+            let (sin0, cos0) = a0.sin_cos();
+            let (sin1, cos1) = a1.sin_cos();
+            [
+                (cx + radius * cos0 - k * sin0, cy + radius * sin0 + k * cos0),
+                (cx + radius * cos1 + k * sin1, cy + radius * sin1 - k * cos1),
+                (cx + radius * cos1, cy + radius * sin1),
+            ]
+        })
+        .collect()
+}
+
+/// Where the turtle was after something changed on the canvas, used to replay the drawing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Step {
+    pub position: (f32, f32),
+    pub direction: f32,
+    changes: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Erased {
+    shape: Shape,
+    drawn: usize,
+    erased: usize,
+}
+
+/// Everything the turtle drew, in painting order, independent of any output format.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DisplayList {
+    pub view: (f32, f32, f32, f32),
+    pub background: Color,
+    pub shapes: Vec<Shape>,
+    pub steps: Option<Vec<Step>>,
+    drawn: Vec<usize>,
+    erased: Vec<Erased>,
+    changes: usize,
+}
+
+impl DisplayList {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            view: (0.0, 0.0, width as f32, height as f32),
+            background: Color::black(),
+            shapes: Vec::new(),
+            steps: None,
+            drawn: Vec::new(),
+            erased: Vec::new(),
+            changes: 0,
+        }
+    }
+
+    pub fn start_recording(&mut self) {
+        self.steps = Some(Vec::new());
+    }
+
+    /// Records a step if anything was drawn or cleared since the last one, or always when
+    /// `force` is set.
+    pub fn record(&mut self, position: (f32, f32), direction: f32, force: bool) {
+        let changes = self.changes;
+        let Some(steps) = &mut self.steps else {
+            return;
+        };
+        let step = Step {
+            position,
+            direction,
+            changes,
+        };
+        match steps.last() {
+            Some(last) if *last == step => {}
+            Some(last) if last.changes == changes && !force => {}
+            None if changes == 0 && !force => {}
+            _ => steps.push(step),
+        }
+    }
+
+    /// The index of the first step that shows a change, or the number of steps if none does.
+    fn step_of(&self, change: usize) -> usize {
+        self.steps
+            .as_deref()
+            .unwrap_or_default()
+            .partition_point(|step| step.changes <= change)
+    }
+
+    /// The canvas as it looked at the given step, without any recording.
+    pub fn frame(&self, step: usize) -> DisplayList {
+        let changes = self.steps.as_deref().unwrap_or_default()[step].changes;
+        let erased = self
+            .erased
+            .iter()
+            .filter(|erased| erased.drawn < changes && changes <= erased.erased)
+            .map(|erased| erased.shape.clone());
+        let shapes = self
+            .shapes
+            .iter()
+            .zip(&self.drawn)
+            .filter(|(_, &drawn)| drawn < changes)
+            .map(|(shape, _)| shape.clone());
+
+        let mut frame = DisplayList::new(0, 0);
+        frame.view = self.view;
+        frame.background = self.background;
+        for shape in erased.chain(shapes) {
+            frame.push(shape);
+        }
+        frame
+    }
+
+    /// Every shape ever drawn in painting order, with the step it appears at and the step it
+    /// is cleared at, if any.
+    pub fn history(&self) -> Vec<(&Shape, usize, Option<usize>)> {
+        let erased = self.erased.iter().map(|erased| {
+            (
+                &erased.shape,
+                self.step_of(erased.drawn),
+                Some(self.step_of(erased.erased)),
+            )
+        });
+        let shapes = self
+            .shapes
+            .iter()
+            .zip(&self.drawn)
+            .map(|(shape, &drawn)| (shape, self.step_of(drawn), None));
+        erased.chain(shapes).collect()
+    }
+
+    pub fn size(&self) -> (f32, f32) {
+        (self.view.2, self.view.3)
+    }
+
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn push(&mut self, shape: Shape) {
+        self.insert(self.shapes.len(), shape);
+    }
+
+    pub fn insert(&mut self, index: usize, shape: Shape) {
+        let index = index.min(self.shapes.len());
+        self.shapes.insert(index, shape);
+        self.drawn.insert(index, self.changes);
+        self.changes += 1;
+    }
+
+    pub fn clear(&mut self) {
+        let shapes = std::mem::take(&mut self.shapes);
+        let drawn = std::mem::take(&mut self.drawn);
+        if self.steps.is_some() {
+            self.erased
+                .extend(shapes.into_iter().zip(drawn).map(|(shape, drawn)| Erased {
+                    shape,
+                    drawn,
+                    erased: self.changes,
+                }));
+        }
+        self.changes += 1;
+    }
+
+    pub fn bounds(&self) -> Option<(f32, f32, f32, f32)> {
+        let mut bounds: Option<(f32, f32, f32, f32)> = None;
+        let mut include = |(x, y): (f32, f32), pad: f32| {
+            let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((x, y, x, y));
+            bounds = Some((
+                min_x.min(x - pad),
+                min_y.min(y - pad),
+                max_x.max(x + pad),
+                max_y.max(y + pad),
+            ));
+        };
+
+        let erased = self.erased.iter().map(|erased| &erased.shape);
+        for shape in erased.chain(&self.shapes) {
+            match shape {
+                Shape::Line {
+                    start, end, width, ..
+                } => {
+                    include(*start, width / 2.0);
+                    include(*end, width / 2.0);
+                }
+                Shape::Arc {
+                    start,
+                    end,
+                    center: (cx, cy),
+                    radius,
+                    angle,
+                    width,
+                    ..
+                } => {
+                    let from = (start.1 - cy).atan2(start.0 - cx).to_degrees();
+                    let (from, to) = (from.min(from + angle), from.max(from + angle));
+                    let mut quarter = (from / 90.0).ceil() * 90.0;
+                    while quarter <= to {
+                        let (sin, cos) = quarter.to_radians().sin_cos();
+                        include((cx + radius * cos, cy + radius * sin), width / 2.0);
+                        quarter += 90.0;
+                    }
+                    include(*start, width / 2.0);
+                    include(*end, width / 2.0);
+                }
+                Shape::Circle {
+                    center,
+                    radius,
+                    width,
+                    ..
+                } => include(*center, radius + width / 2.0),
+                Shape::Fill { points, .. } => {
+                    for &point in points {
+                        include(point, 0.0);
+                    }
+                }
+                Shape::Label {
+                    text,
+                    position: (x, y),
+                    direction,
+                    height,
+                    ..
+                } => {
+                    let length = text.chars().count() as f32 * height * 0.6;
+                    let (sin, cos) = (direction - 90.0).to_radians().sin_cos();
+                    for (dx, dy) in [(0.0, 0.0), (length, 0.0), (0.0, -height), (length, -height)] {
+                        include((x + dx * cos - dy * sin, y + dx * sin + dy * cos), 0.0);
+                    }
+                }
+            }
+        }
+        bounds
+    }
+
+    pub fn fit(&mut self, margin: f32) {
+        let Some((min_x, min_y, max_x, max_y)) = self.bounds() else {
+            return;
+        };
+        let (x, y) = ((min_x - margin).floor(), (min_y - margin).floor());
+        let width = ((max_x + margin).ceil() - x).max(1.0);
+        let height = ((max_y + margin).ceil() - y).max(1.0);
+        self.view = (x, y, width, height);
+    }
+}
@@ -1,24 +1,93 @@
-use unsvg::*;
+use crate::generation::display::{DisplayList, Shape};
+use crate::generation::image::get_end_coordinates;
+use std::collections::HashSet;
+use unsvg::{Color, COLORS};
+
+/// Pieces of a wrapped line that start within this many pixels of each other are drawn once.
+const WRAP_TOLERANCE: f64 = 0.25;
+
+#[derive(Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+pub enum Boundary {
+    Window,
+    Wrap,
+    Fence,
+}
+
+pub struct Canvas {
+    pub width: u32,
+    pub height: u32,
+    pub origin: (f32, f32),
+    pub heading: f32,
+    pub boundary: Boundary,
+    pub auto_size: Option<f32>,
+    pub animate: bool,
+}
+
+impl Canvas {
+    pub fn new(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            origin: (width as f32 / 2.0, height as f32 / 2.0),
+            heading: 0.0,
+            boundary: Boundary::Window,
+            auto_size: None,
+            animate: false,
+        }
+    }
+}
+
+struct Turtle {
+    x: f32,
+    y: f32,
+    direction: f32,
+    draw: bool,
+    color: Color,
+    width: f32,
+}
 
 pub struct Draw<'a> {
     x: f32,
     y: f32,
     draw: bool,
     color: Color,
-    direction: i32,
-    image: &'a mut Image,
+    /// The 16 default colours, followed by every other colour `COLOR` or `FILLCOLOR` has
+    /// reported.
+    palette: Vec<Color>,
+    width: f32,
+    fill_color: Color,
+    fill: Option<(usize, Vec<(f32, f32)>)>,
+    label_height: f32,
+    direction: f32,
+    stack: Vec<Turtle>,
+    home: (f32, f32),
+    home_direction: f32,
+    boundary: Boundary,
+    display: &'a mut DisplayList,
 }
 
 impl<'a> Draw<'a> {
-    pub fn new(x: f32, y: f32, image: &'a mut Image) -> Self {
-        Self {
-            x: x / 2.0,
-            y: y / 2.0,
+    pub fn new(canvas: &Canvas, display: &'a mut DisplayList) -> Self {
+        let (x, y) = canvas.origin;
+        let mut draw = Self {
+            x,
+            y,
             draw: false,
             color: COLORS[7],
-            direction: 0,
-            image,
-        }
+            palette: COLORS.to_vec(),
+            width: 1.0,
+            fill_color: COLORS[7],
+            fill: None,
+            label_height: 12.0,
+            direction: canvas.heading.rem_euclid(360.0),
+            stack: Vec::new(),
+            home: (x, y),
+            home_direction: canvas.heading.rem_euclid(360.0),
+            boundary: Boundary::Window,
+            display,
+        };
+        draw.set_boundary(canvas.boundary);
+        draw
     }
 
     pub fn x(&self) -> f32 {
@@ -29,11 +98,38 @@ impl<'a> Draw<'a> {
         self.y
     }
 
-    pub fn color(&self) -> usize {
-        COLORS.iter().position(|&c| c == self.color).unwrap()
+    /// The palette index of the pen colour. A colour that is not in the palette, set by
+    /// `SETPENRGB` or a hex code, is appended to it, so `SETPENCOLOR COLOR` always selects the
+    /// same colour again.
+    pub fn color(&mut self) -> usize {
+        self.palette_index(self.color)
+    }
+
+    /// The palette index of the fill colour, added to the palette the same way as `color`.
+    pub fn fill_color(&mut self) -> usize {
+        self.palette_index(self.fill_color)
+    }
+
+    fn palette_index(&mut self, color: Color) -> usize {
+        match self.palette.iter().position(|&c| c == color) {
+            Some(index) => index,
+            None => {
+                self.palette.push(color);
+                self.palette.len() - 1
+            }
+        }
+    }
+
+    /// How many colours `SETPENCOLOR`, `SETFILLCOLOR` and `SETBACKGROUND` can select.
+    pub fn palette_len(&self) -> usize {
+        self.palette.len()
     }
 
-    pub fn direction(&self) -> i32 {
+    pub fn pen_size(&self) -> f32 {
+        self.width
+    }
+
+    pub fn direction(&self) -> f32 {
         self.direction
     }
 
@@ -45,49 +141,336 @@ impl<'a> Draw<'a> {
         self.draw = true;
     }
 
-    pub fn pen_move(&mut self, direction: i32, mut distance: f32) {
+    pub fn pen_move(&mut self, direction: f32, mut distance: f32) -> Result<(), &'static str> {
         let mut dir = self.direction + direction;
 
         if distance < 0f32 {
-            dir += 180;
+            dir += 180.0;
             distance = distance.abs();
         }
 
-        if !self.draw {
-            let temp = get_end_coordinates(self.x, self.y, dir, distance);
-            self.x = temp.0;
-            self.y = temp.1;
-        } else {
-            match self
-                .image
-                .draw_simple_line(self.x, self.y, dir, distance, self.color)
-            {
-                Ok(result) => {
-                    self.x = result.0;
-                    self.y = result.1;
+        let (x, y) = get_end_coordinates(self.x, self.y, dir, distance);
+        self.set_pos(x, y)
+    }
+
+    pub fn set_pos(&mut self, x: f32, y: f32) -> Result<(), &'static str> {
+        let (x, y) = match self.boundary {
+            Boundary::Fence if !self.inside(x, y) => return Err("TurtleOutOfBounds"),
+            Boundary::Wrap => self.wrap_line(x, y),
+            _ => {
+                self.line((self.x, self.y), (x, y));
+                (x, y)
+            }
+        };
+        self.x = x;
+        self.y = y;
+        self.visit();
+        self.record();
+        Ok(())
+    }
+
+    pub fn home(&mut self) -> Result<(), &'static str> {
+        self.direction = self.home_direction;
+        self.set_pos(self.home.0, self.home.1)
+    }
+
+    pub fn clear_screen(&mut self) {
+        self.display.clear();
+        (self.x, self.y) = self.wrap(self.home.0, self.home.1);
+        self.direction = self.home_direction;
+        if self.fill.is_some() {
+            self.fill = Some((self.display.len(), vec![(self.x, self.y)]));
+        }
+        self.record();
+    }
+
+    pub fn towards(&self, x: f32, y: f32) -> f32 {
+        (x - self.x)
+            .atan2(self.y - y)
+            .to_degrees()
+            .rem_euclid(360.0)
+    }
+
+    pub fn arc(&mut self, angle: f32, radius: f32) -> Result<(), &'static str> {
+        let side = 90f32.copysign(angle);
+        let (cx, cy) = get_end_coordinates(self.x, self.y, self.direction + side, radius);
+        let rest = angle % 360.0;
+        let (x, y) = get_end_coordinates(cx, cy, self.direction + rest - side, radius);
+
+        // Turns past the first one retrace the same circle, so they add nothing to check or fill.
+        let sweep = match angle.abs() >= 360.0 {
+            true => 360f32.copysign(angle) + rest,
+            false => angle,
+        };
+        let points = match self.boundary == Boundary::Fence || self.fill.is_some() {
+            true => {
+                let steps = (sweep.abs() / 10.0).ceil() as usize;
+                (1..=steps)
+                    .map(|step| {
+                        let heading = self.direction + sweep * step as f32 / steps as f32 - side;
+                        get_end_coordinates(cx, cy, heading, radius)
+                    })
+                    .collect::<Vec<_>>()
+            }
+            false => Vec::new(),
+        };
+        if self.boundary == Boundary::Fence && !points.iter().all(|&(x, y)| self.inside(x, y)) {
+            return Err("TurtleOutOfBounds");
+        }
+
+        if self.draw {
+            for (dx, dy) in self.copies((cx - radius, cy - radius), (cx + radius, cy + radius)) {
+                if angle.abs() >= 360.0 {
+                    self.display.push(Shape::Circle {
+                        center: (cx + dx, cy + dy),
+                        radius,
+                        color: self.color,
+                        width: self.width,
+                    });
+                }
+                if rest != 0.0 {
+                    self.display.push(Shape::Arc {
+                        start: (self.x + dx, self.y + dy),
+                        end: (x + dx, y + dy),
+                        center: (cx + dx, cy + dy),
+                        radius,
+                        angle: rest,
+                        color: self.color,
+                        width: self.width,
+                    });
                 }
-                Err(e) => eprintln!("Error drawing line: {e}"),
             }
         }
+
+        if let Some((_, fill)) = &mut self.fill {
+            fill.extend(points);
+        }
+
+        (self.x, self.y) = self.wrap(x, y);
+        self.direction = (self.direction + angle).rem_euclid(360.0);
+        self.record();
+        Ok(())
+    }
+
+    pub fn set_boundary(&mut self, boundary: Boundary) {
+        self.boundary = boundary;
+        (self.x, self.y) = self.wrap(self.x, self.y);
+    }
+
+    fn inside(&self, x: f32, y: f32) -> bool {
+        let (width, height) = self.display.size();
+        (0.0..=width).contains(&x) && (0.0..=height).contains(&y)
+    }
+
+    fn wrap(&self, x: f32, y: f32) -> (f32, f32) {
+        let (width, height) = self.display.size();
+        match self.boundary {
+            Boundary::Wrap => (x.rem_euclid(width), y.rem_euclid(height)),
+            _ => (x, y),
+        }
+    }
+
+    fn copies(&self, (x1, y1): (f32, f32), (x2, y2): (f32, f32)) -> Vec<(f32, f32)> {
+        if self.boundary != Boundary::Wrap {
+            return vec![(0.0, 0.0)];
+        }
+
+        let (width, height) = self.display.size();
+        let columns = (-x2 / width).ceil() as i32..=((width - x1) / width).floor() as i32;
+        let rows = (-y2 / height).ceil() as i32..=((height - y1) / height).floor() as i32;
+        columns
+            .flat_map(|i| {
+                rows.clone()
+                    .map(move |j| (i as f32 * width, j as f32 * height))
+            })
+            .collect()
+    }
+
+    fn line(&mut self, start: (f32, f32), end: (f32, f32)) {
+        if self.draw {
+            self.display.push(Shape::Line {
+                start,
+                end,
+                color: self.color,
+                width: self.width,
+            });
+        }
+    }
+
+    /// Draws the line to `(x, y)` in pieces, continuing from the opposite edge each time it
+    /// leaves the canvas. Works in `f64` so that long moves still make progress on every piece.
+    fn wrap_line(&mut self, x: f32, y: f32) -> (f32, f32) {
+        let (width, height) = self.display.size();
+        let (width, height) = (width as f64, height as f64);
+        let (mut x1, mut y1) = (self.x as f64, self.y as f64);
+        let (mut x2, mut y2) = (x as f64, y as f64);
+
+        // Every piece crosses the canvas the same way from where it starts, so once a start
+        // repeats, the rest of the line only redraws pieces that are already there.
+        let mut starts = HashSet::new();
+        while starts.insert((
+            (x1 / WRAP_TOLERANCE).round() as i64,
+            (y1 / WRAP_TOLERANCE).round() as i64,
+        )) {
+            let mut t = 1.0;
+            let mut shift = (0.0, 0.0);
+            if x2 > width || x2 < 0.0 {
+                let edge = if x2 > width { width } else { 0.0 };
+                t = (edge - x1) / (x2 - x1);
+                shift = (-width.copysign(x2 - x1), 0.0);
+            }
+            if y2 > height || y2 < 0.0 {
+                let edge = if y2 > height { height } else { 0.0 };
+                let ty = (edge - y1) / (y2 - y1);
+                if ty < t {
+                    t = ty;
+                    shift = (0.0, -height.copysign(y2 - y1));
+                }
+            }
+
+            let (x, y) = (x1 + (x2 - x1) * t, y1 + (y2 - y1) * t);
+            if t > 0.0 {
+                self.line((x1 as f32, y1 as f32), (x as f32, y as f32));
+            }
+            if t >= 1.0 {
+                return (x2 as f32, y2 as f32);
+            }
+
+            (x1, y1) = (x + shift.0, y + shift.1);
+            (x2, y2) = (x2 + shift.0, y2 + shift.1);
+        }
+        self.wrap(x, y)
+    }
+
+    pub fn push_turtle(&mut self) {
+        self.stack.push(Turtle {
+            x: self.x,
+            y: self.y,
+            direction: self.direction,
+            draw: self.draw,
+            color: self.color,
+            width: self.width,
+        });
+    }
+
+    pub fn pop_turtle(&mut self) -> bool {
+        let Some(turtle) = self.stack.pop() else {
+            return false;
+        };
+        (self.x, self.y) = self.wrap(turtle.x, turtle.y);
+        self.direction = turtle.direction;
+        self.draw = turtle.draw;
+        self.color = turtle.color;
+        self.width = turtle.width;
+        self.visit();
+        true
     }
 
     pub fn set_pen_color(&mut self, index: usize) {
-        self.color = COLORS[index];
+        self.color = self.palette[index];
     }
 
-    pub fn turn(&mut self, angle: i32) {
-        self.direction += angle;
+    pub fn set_pen_size(&mut self, width: f32) {
+        self.width = width;
     }
 
-    pub fn set_heading(&mut self, angle: i32) {
-        self.direction = angle;
+    pub fn set_fill_color(&mut self, index: usize) {
+        self.fill_color = self.palette[index];
     }
 
-    pub fn set_x(&mut self, x: f32) {
-        self.x = x;
+    pub fn begin_fill(&mut self) -> bool {
+        if self.fill.is_some() {
+            return false;
+        }
+        self.fill = Some((self.display.len(), vec![(self.x, self.y)]));
+        true
     }
 
-    pub fn set_y(&mut self, y: f32) {
-        self.y = y;
+    pub fn end_fill(&mut self) -> bool {
+        let Some((index, points)) = self.fill.take() else {
+            return false;
+        };
+        if points.len() > 2 {
+            self.display.insert(
+                index,
+                Shape::Fill {
+                    points,
+                    color: self.fill_color,
+                },
+            );
+        }
+        self.record();
+        true
+    }
+
+    pub fn label(&mut self, text: &str) {
+        self.display.push(Shape::Label {
+            text: text.to_string(),
+            position: (self.x, self.y),
+            direction: self.direction,
+            height: self.label_height,
+            color: self.color,
+        });
+        self.record();
+    }
+
+    pub fn set_label_height(&mut self, height: f32) {
+        self.label_height = height;
+    }
+
+    fn record(&mut self) {
+        self.display.record((self.x, self.y), self.direction, false);
+    }
+
+    /// Records where the turtle ended up, even if it has not drawn anything since.
+    pub fn finish(&mut self) {
+        self.display.record((self.x, self.y), self.direction, true);
+    }
+
+    fn visit(&mut self) {
+        if let Some((_, points)) = &mut self.fill {
+            points.push((self.x, self.y));
+        }
+    }
+
+    pub fn set_background(&mut self, index: usize) {
+        self.display.background = self.palette[index];
+    }
+
+    pub fn set_background_rgb(&mut self, red: u8, green: u8, blue: u8) {
+        self.display.background = Color::new_rgb(red, green, blue);
+    }
+
+    pub fn set_pen_rgb(&mut self, red: u8, green: u8, blue: u8) {
+        self.color = Color::new_rgb(red, green, blue);
+    }
+
+    pub fn set_palette(&mut self, index: usize, red: u8, green: u8, blue: u8) {
+        self.palette[index] = Color::new_rgb(red, green, blue);
+    }
+
+    pub fn turn(&mut self, angle: f32) {
+        self.direction = (self.direction + angle).rem_euclid(360.0);
+    }
+
+    pub fn set_heading(&mut self, angle: f32) {
+        self.direction = angle.rem_euclid(360.0);
+    }
+
+    pub fn set_x(&mut self, x: f32) -> Result<(), &'static str> {
+        self.jump(x, self.y)
+    }
+
+    pub fn set_y(&mut self, y: f32) -> Result<(), &'static str> {
+        self.jump(self.x, y)
+    }
+
+    fn jump(&mut self, x: f32, y: f32) -> Result<(), &'static str> {
+        if self.boundary == Boundary::Fence && !self.inside(x, y) {
+            return Err("TurtleOutOfBounds");
+        }
+        (self.x, self.y) = self.wrap(x, y);
+        self.visit();
+        Ok(())
     }
 }
@@ -29,6 +29,38 @@ pub enum GenerationError<'a> {
         help: &'a str,
         error: String,
     },
+    NegativeSquareRoot {
+        #[source_code]
+        src: String,
+        #[label("Cannot take the square root of a negative number.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    ModByZero {
+        #[source_code]
+        src: String,
+        #[label("Cannot take the remainder of a division by zero.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    InvalidPower {
+        #[source_code]
+        src: String,
+        #[label("`POWER` has no finite real result for these values.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    InvalidRandomRange {
+        #[source_code]
+        src: String,
+        #[label("`RANDOM` expects a positive integer.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
     UnmatchedExprType {
         #[source_code]
         src: String,
@@ -45,6 +77,22 @@ pub enum GenerationError<'a> {
         help: &'a str,
         error: String,
     },
+    InvalidAngleValue {
+        #[source_code]
+        src: String,
+        #[label("Expected a finite angle in degrees.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    InvalidDistance {
+        #[source_code]
+        src: String,
+        #[label("Expected a finite distance or coordinate.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
     UnDefinedColor {
         #[source_code]
         src: String,
@@ -53,6 +101,62 @@ pub enum GenerationError<'a> {
         help: &'a str,
         error: String,
     },
+    InvalidRgbValue {
+        #[source_code]
+        src: String,
+        #[label("RGB channels only pick integer value from 0 to 255")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    InvalidPenSize {
+        #[source_code]
+        src: String,
+        #[label("Pen size must be a positive number")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    InvalidRadius {
+        #[source_code]
+        src: String,
+        #[label("Radius must be a positive number")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    InvalidLabelHeight {
+        #[source_code]
+        src: String,
+        #[label("Label height must be a positive number")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    TurtleOutOfBounds {
+        #[source_code]
+        src: String,
+        #[label("The turtle would leave the canvas")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    EmptyTurtleStack {
+        #[source_code]
+        src: String,
+        #[label("No turtle state has been pushed")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    UnmatchedFill {
+        #[source_code]
+        src: String,
+        #[label("`BEGINFILL` and `ENDFILL` must come in pairs")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
     UnDefinedVariable {
         #[source_code]
         src: String,
@@ -69,6 +173,14 @@ pub enum GenerationError<'a> {
         help: &'a str,
         error: String,
     },
+    UnDefinedRepCount {
+        #[source_code]
+        src: String,
+        #[label("`REPCOUNT` is only available inside a `REPEAT` block.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
     UnDefinedFunction {
         #[source_code]
         src: String,
@@ -77,6 +189,14 @@ pub enum GenerationError<'a> {
         help: &'a str,
         error: String,
     },
+    MissingOutput {
+        #[source_code]
+        src: String,
+        #[label("Function finished without `OUTPUT`.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
     TooManyArguments {
         #[source_code]
         src: String,
@@ -93,6 +213,75 @@ pub enum GenerationError<'a> {
         help: &'a str,
         error: String,
     },
+    RecursionLimitExceeded {
+        #[source_code]
+        src: String,
+        #[label("Maximum recursion depth exceeded by this call.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+    StepLimitExceeded {
+        #[source_code]
+        src: String,
+        #[label("Execution limit reached while running this statement.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
+}
+
+impl GenerationError<'_> {
+    pub fn trace(mut self, name: &str, line: usize) -> Self {
+        match &mut self {
+            GenerationError::UnexpectedNumberType { error, .. }
+            | GenerationError::UnexpectedBooleanType { error, .. }
+            | GenerationError::DivideByZero { error, .. }
+            | GenerationError::NegativeSquareRoot { error, .. }
+            | GenerationError::ModByZero { error, .. }
+            | GenerationError::InvalidPower { error, .. }
+            | GenerationError::InvalidRandomRange { error, .. }
+            | GenerationError::UnmatchedExprType { error, .. }
+            | GenerationError::NonIntegerValueError { error, .. }
+            | GenerationError::InvalidAngleValue { error, .. }
+            | GenerationError::InvalidDistance { error, .. }
+            | GenerationError::UnDefinedColor { error, .. }
+            | GenerationError::InvalidRgbValue { error, .. }
+            | GenerationError::InvalidPenSize { error, .. }
+            | GenerationError::InvalidRadius { error, .. }
+            | GenerationError::InvalidLabelHeight { error, .. }
+            | GenerationError::TurtleOutOfBounds { error, .. }
+            | GenerationError::EmptyTurtleStack { error, .. }
+            | GenerationError::UnmatchedFill { error, .. }
+            | GenerationError::UnDefinedVariable { error, .. }
+            | GenerationError::UnDefinedVariableValue { error, .. }
+            | GenerationError::UnDefinedRepCount { error, .. }
+            | GenerationError::UnDefinedFunction { error, .. }
+            | GenerationError::MissingOutput { error, .. }
+            | GenerationError::TooManyArguments { error, .. }
+            | GenerationError::MissingArguments { error, .. }
+            | GenerationError::RecursionLimitExceeded { error, .. }
+            | GenerationError::StepLimitExceeded { error, .. } => {
+                let frame = format!("\n    in `{name}` called at Ln {line}");
+                let (head, last) = error.split_at(error.rfind('\n').unwrap_or(error.len()));
+
+                let count = if last == frame {
+                    Some(2)
+                } else {
+                    last.strip_prefix(&format!("{frame} (x"))
+                        .and_then(|count| count.strip_suffix(')'))
+                        .and_then(|count| count.parse::<usize>().ok())
+                        .map(|count| count + 1)
+                };
+
+                *error = match count {
+                    Some(count) => format!("{head}{frame} (x{count})"),
+                    None => format!("{error}{frame}"),
+                };
+            }
+        }
+        self
+    }
 }
 
 pub fn match_err<'a>(
@@ -118,7 +307,9 @@ pub fn match_err<'a>(
             src,
             bad_bit: (start, len).into(),
             help: "Replace with an expression that returns a number, choosing from `FLOAT`, \
-            `VAR`, `ADD`, `SUB`, `MUL`, `DIV`, `XCOR`, `YCOR`, `HEADING`, or `COLOR`.",
+            `VAR`, `ADD`, `SUB`, `MUL`, `DIV`, `XCOR`, `YCOR`, `HEADING`, `COLOR`, `FILLCOLOR`, \
+            `PENSIZE`, `REPCOUNT`, \
+            or a math function such as `SIN` or `SQRT`.",
             error: format!(
                 "Unexpected `boolean` type in expression (Ln {line}, Col {})",
                 start + 1
@@ -130,6 +321,30 @@ pub fn match_err<'a>(
             help: "Change the divisor to a non-zero value or check the variable value.",
             error: format!("Divide by zero error (Ln {line}, Col {})", start + 1),
         },
+        "NegativeSquareRoot" => GenerationError::NegativeSquareRoot {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Make sure the value passed to `SQRT` is zero or positive, or wrap it in `ABS`.",
+            error: format!("Negative square root error (Ln {line}, Col {})", start + 1),
+        },
+        "ModByZero" => GenerationError::ModByZero {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Change the divisor of `MOD` to a non-zero value or check the variable value.",
+            error: format!("Modulo by zero error (Ln {line}, Col {})", start + 1),
+        },
+        "InvalidPower" => GenerationError::InvalidPower {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Raise a negative base only to whole powers, and zero only to positive powers.",
+            error: format!("Invalid power error (Ln {line}, Col {})", start + 1),
+        },
+        "InvalidRandomRange" => GenerationError::InvalidRandomRange {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Change the value passed to `RANDOM` to an integer greater than zero.",
+            error: format!("Invalid random range error (Ln {line}, Col {})", start + 1),
+        },
         "UnmatchedExprType" => GenerationError::UnmatchedExprType {
             src,
             bad_bit: (start, len).into(),
@@ -142,12 +357,66 @@ pub fn match_err<'a>(
             help: "Change the value to an integer value or check the variable value.",
             error: format!("Non-integer value error (Ln {line}, Col {})", start + 1),
         },
+        "InvalidAngleValue" => GenerationError::InvalidAngleValue {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Check the expression for an overflow, the angle must not be infinite or NaN.",
+            error: format!("Invalid angle value error (Ln {line}, Col {})", start + 1),
+        },
+        "InvalidDistance" => GenerationError::InvalidDistance {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Check the expression for an overflow, the distance must not be infinite or NaN.",
+            error: format!("Invalid distance error (Ln {line}, Col {})", start + 1),
+        },
         "UnDefinedColor" => GenerationError::UnDefinedColor {
             src,
             bad_bit: (start, len).into(),
-            help: "Change the color value to an integer value from 0 to 15.",
+            help: "Change the color value to an integer value from 0 to 15, or to a value reported by `COLOR` or `FILLCOLOR`.",
             error: format!("Undefined color error (Ln {line}, Col {})", start + 1),
         },
+        "InvalidRgbValue" => GenerationError::InvalidRgbValue {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Change the red, green and blue values to integer values from 0 to 255.",
+            error: format!("Invalid RGB value error (Ln {line}, Col {})", start + 1),
+        },
+        "InvalidPenSize" => GenerationError::InvalidPenSize {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Change the pen size to a number greater than 0.",
+            error: format!("Invalid pen size error (Ln {line}, Col {})", start + 1),
+        },
+        "InvalidRadius" => GenerationError::InvalidRadius {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Change the radius to a number greater than 0.",
+            error: format!("Invalid radius error (Ln {line}, Col {})", start + 1),
+        },
+        "InvalidLabelHeight" => GenerationError::InvalidLabelHeight {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Change the label height to a number greater than 0.",
+            error: format!("Invalid label height error (Ln {line}, Col {})", start + 1),
+        },
+        "TurtleOutOfBounds" => GenerationError::TurtleOutOfBounds {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Keep the turtle inside the canvas, or use `WRAP` or `WINDOW` to let it leave.",
+            error: format!("Turtle out of bounds error (Ln {line}, Col {})", start + 1),
+        },
+        "EmptyTurtleStack" => GenerationError::EmptyTurtleStack {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Use `PUSHTURTLE` to save the turtle before each `POPTURTLE`.",
+            error: format!("Empty turtle stack error (Ln {line}, Col {})", start + 1),
+        },
+        "UnmatchedFill" => GenerationError::UnmatchedFill {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Close each `BEGINFILL` with an `ENDFILL` before starting another fill.",
+            error: format!("Unmatched fill error (Ln {line}, Col {})", start + 1),
+        },
         "UnDefinedVariable" => GenerationError::UnDefinedVariable {
             src,
             bad_bit: (start, len).into(),
@@ -157,19 +426,36 @@ pub fn match_err<'a>(
         "UnDefinedVariableValue" => GenerationError::UnDefinedVariableValue {
             src,
             bad_bit: (start, len).into(),
-            help: "This value may be defined in the arguments of `TO`, but its specific value \
-                has not been defined yet and cannot be used.",
+            help: "This variable was declared with `LOCAL`, but no value has been assigned \
+                to it with `MAKE` yet.",
             error: format!(
                 "Undefined variable value error (Ln {line}, Col {})",
                 start + 1
             ),
         },
+        "UnDefinedRepCount" => GenerationError::UnDefinedRepCount {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Move `REPCOUNT` into the body of a `REPEAT` statement in the same procedure, \
+                or pass the count to the procedure as an argument.",
+            error: format!(
+                "Undefined repeat count error (Ln {line}, Col {})",
+                start + 1
+            ),
+        },
         "UnDefinedFunction" => GenerationError::UnDefinedFunction {
             src,
             bad_bit: (start, len).into(),
             help: "Define the `TO` function before using it.",
             error: format!("Undefined function error (Ln {line}, Col {})", start + 1),
         },
+        "MissingOutput" => GenerationError::MissingOutput {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Make sure every path through the function reaches an `OUTPUT` statement \
+                before it is used in an expression.",
+            error: format!("Missing output error (Ln {line}, Col {})", start + 1),
+        },
         "TooManyArguments" => GenerationError::TooManyArguments {
             src,
             bad_bit: (start, len).into(),
@@ -182,6 +468,23 @@ pub fn match_err<'a>(
             help: "Add the missing arguments to the function call.",
             error: format!("Missing arguments error (Ln {line}, Col {})", start + 1),
         },
+        "RecursionLimitExceeded" => GenerationError::RecursionLimitExceeded {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Check that the recursive function has a base case that stops it, \
+                or raise the limit with `--max-depth`.",
+            error: format!(
+                "Recursion limit exceeded error (Ln {line}, Col {})",
+                start + 1
+            ),
+        },
+        "StepLimitExceeded" => GenerationError::StepLimitExceeded {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Check that the loop condition eventually becomes false, \
+                or raise the limit with `--max-steps` or `--timeout`.",
+            error: format!("Step limit exceeded error (Ln {line}, Col {})", start + 1),
+        },
         _ => unreachable!(),
     }
 }
@@ -1,7 +1,8 @@
-use crate::ast::structs::Expr;
+use crate::ast::structs::{Expr, Function};
 use crate::generation::{
     draw::Draw,
     err::{match_err, GenerationError},
+    stmt::{process_call, Flow},
     variable::{Type, Variable},
 };
 
@@ -13,10 +14,12 @@ pub enum Value {
 
 pub fn process_expr(
     expr: &Expr,
-    variable: &Variable,
+    variable: &mut Variable,
     line: usize,
     sentence: &str,
-    draw: &Draw,
+    draw: &mut Draw,
+    function: &Function,
+    file: &[String],
 ) -> Result<Value, GenerationError<'static>> {
     match expr {
         Expr::Boolean(bool, ..) => Ok(Value::B(*bool)),
@@ -40,8 +43,12 @@ pub fn process_expr(
             )),
         },
         Expr::Add(expr1, expr2, end, len) => {
-            if let Value::F(num1) = process_expr(expr1, variable, line, sentence, draw)? {
-                if let Value::F(num2) = process_expr(expr2, variable, line, sentence, draw)? {
+            if let Value::F(num1) =
+                process_expr(expr1, variable, line, sentence, draw, function, file)?
+            {
+                if let Value::F(num2) =
+                    process_expr(expr2, variable, line, sentence, draw, function, file)?
+                {
                     Ok(Value::F(num1 + num2))
                 } else {
                     Err(match_err(
@@ -63,8 +70,12 @@ pub fn process_expr(
             }
         }
         Expr::Sub(expr1, expr2, end, len) => {
-            if let Value::F(num1) = process_expr(expr1, variable, line, sentence, draw)? {
-                if let Value::F(num2) = process_expr(expr2, variable, line, sentence, draw)? {
+            if let Value::F(num1) =
+                process_expr(expr1, variable, line, sentence, draw, function, file)?
+            {
+                if let Value::F(num2) =
+                    process_expr(expr2, variable, line, sentence, draw, function, file)?
+                {
                     Ok(Value::F(num1 - num2))
                 } else {
                     Err(match_err(
@@ -86,8 +97,12 @@ pub fn process_expr(
             }
         }
         Expr::Mul(expr1, expr2, end, len) => {
-            if let Value::F(num1) = process_expr(expr1, variable, line, sentence, draw)? {
-                if let Value::F(num2) = process_expr(expr2, variable, line, sentence, draw)? {
+            if let Value::F(num1) =
+                process_expr(expr1, variable, line, sentence, draw, function, file)?
+            {
+                if let Value::F(num2) =
+                    process_expr(expr2, variable, line, sentence, draw, function, file)?
+                {
                     Ok(Value::F(num1 * num2))
                 } else {
                     Err(match_err(
@@ -109,8 +124,12 @@ pub fn process_expr(
             }
         }
         Expr::Div(expr1, expr2, end, len) => {
-            if let Value::F(num1) = process_expr(expr1, variable, line, sentence, draw)? {
-                if let Value::F(num2) = process_expr(expr2, variable, line, sentence, draw)? {
+            if let Value::F(num1) =
+                process_expr(expr1, variable, line, sentence, draw, function, file)?
+            {
+                if let Value::F(num2) =
+                    process_expr(expr2, variable, line, sentence, draw, function, file)?
+                {
                     if num2 == 0.0 {
                         return Err(match_err(
                             sentence.to_string(),
@@ -142,8 +161,8 @@ pub fn process_expr(
         }
         Expr::Eq(expr1, expr2, end, len) => {
             match (
-                process_expr(expr1, variable, line, sentence, draw)?,
-                process_expr(expr2, variable, line, sentence, draw)?,
+                process_expr(expr1, variable, line, sentence, draw, function, file)?,
+                process_expr(expr2, variable, line, sentence, draw, function, file)?,
             ) {
                 (Value::F(num1), Value::F(num2)) => Ok(Value::B(num1 == num2)),
                 (Value::B(bool1), Value::B(bool2)) => Ok(Value::B(bool1 == bool2)),
@@ -158,8 +177,8 @@ pub fn process_expr(
         }
         Expr::Ne(expr1, expr2, end, len) => {
             match (
-                process_expr(expr1, variable, line, sentence, draw)?,
-                process_expr(expr2, variable, line, sentence, draw)?,
+                process_expr(expr1, variable, line, sentence, draw, function, file)?,
+                process_expr(expr2, variable, line, sentence, draw, function, file)?,
             ) {
                 (Value::F(num1), Value::F(num2)) => Ok(Value::B(num1 != num2)),
                 (Value::B(bool1), Value::B(bool2)) => Ok(Value::B(bool1 != bool2)),
@@ -174,120 +193,261 @@ pub fn process_expr(
         }
         Expr::Lt(expr1, expr2, ..) => {
             match (
-                process_expr(expr1, variable, line, sentence, draw)?,
-                process_expr(expr2, variable, line, sentence, draw)?,
+                process_expr(expr1, variable, line, sentence, draw, function, file)?,
+                process_expr(expr2, variable, line, sentence, draw, function, file)?,
             ) {
                 (Value::F(num1), Value::F(num2)) => Ok(Value::B(num1 < num2)),
                 (Value::B(_), _) => {
                     let (end, len) = get_end_len(expr1);
-                    return Err(match_err(
+                    Err(match_err(
                         sentence.to_string(),
                         line,
                         "UnexpectedBooleanType".to_string(),
                         end,
                         len,
-                    ));
+                    ))
                 }
                 _ => {
                     let (end, len) = get_end_len(expr2);
-                    return Err(match_err(
+                    Err(match_err(
                         sentence.to_string(),
                         line,
                         "UnexpectedBooleanType".to_string(),
                         end,
                         len,
-                    ));
+                    ))
                 }
             }
         }
         Expr::Gt(expr1, expr2, ..) => {
             match (
-                process_expr(expr1, variable, line, sentence, draw)?,
-                process_expr(expr2, variable, line, sentence, draw)?,
+                process_expr(expr1, variable, line, sentence, draw, function, file)?,
+                process_expr(expr2, variable, line, sentence, draw, function, file)?,
             ) {
                 (Value::F(num1), Value::F(num2)) => Ok(Value::B(num1 > num2)),
                 (Value::B(_), _) => {
                     let (end, len) = get_end_len(expr1);
-                    return Err(match_err(
+                    Err(match_err(
                         sentence.to_string(),
                         line,
                         "UnexpectedBooleanType".to_string(),
                         end,
                         len,
-                    ));
+                    ))
                 }
                 _ => {
                     let (end, len) = get_end_len(expr2);
-                    return Err(match_err(
+                    Err(match_err(
                         sentence.to_string(),
                         line,
                         "UnexpectedBooleanType".to_string(),
                         end,
                         len,
-                    ));
+                    ))
                 }
             }
         }
         Expr::And(expr1, expr2, ..) => {
             match (
-                process_expr(expr1, variable, line, sentence, draw)?,
-                process_expr(expr2, variable, line, sentence, draw)?,
+                process_expr(expr1, variable, line, sentence, draw, function, file)?,
+                process_expr(expr2, variable, line, sentence, draw, function, file)?,
             ) {
                 (Value::B(bool1), Value::B(bool2)) => Ok(Value::B(bool1 && bool2)),
                 (Value::F(_), _) => {
                     let (end, len) = get_end_len(expr1);
-                    return Err(match_err(
+                    Err(match_err(
                         sentence.to_string(),
                         line,
                         "UnexpectedNumberType".to_string(),
                         end,
                         len,
-                    ));
+                    ))
                 }
                 _ => {
                     let (end, len) = get_end_len(expr2);
-                    return Err(match_err(
+                    Err(match_err(
                         sentence.to_string(),
                         line,
                         "UnexpectedNumberType".to_string(),
                         end,
                         len,
-                    ));
+                    ))
                 }
             }
         }
         Expr::Or(expr1, expr2, ..) => {
             match (
-                process_expr(expr1, variable, line, sentence, draw)?,
-                process_expr(expr2, variable, line, sentence, draw)?,
+                process_expr(expr1, variable, line, sentence, draw, function, file)?,
+                process_expr(expr2, variable, line, sentence, draw, function, file)?,
             ) {
                 (Value::B(bool1), Value::B(bool2)) => Ok(Value::B(bool1 || bool2)),
                 (Value::F(_), _) => {
                     let (end, len) = get_end_len(expr1);
-                    return Err(match_err(
+                    Err(match_err(
                         sentence.to_string(),
                         line,
                         "UnexpectedNumberType".to_string(),
                         end,
                         len,
-                    ));
+                    ))
                 }
                 _ => {
                     let (end, len) = get_end_len(expr2);
-                    return Err(match_err(
+                    Err(match_err(
                         sentence.to_string(),
                         line,
                         "UnexpectedNumberType".to_string(),
                         end,
                         len,
-                    ));
+                    ))
                 }
             }
         }
         Expr::XCor(..) => Ok(Value::F(draw.x())),
         Expr::YCor(..) => Ok(Value::F(draw.y())),
-        Expr::Heading(..) => Ok(Value::F(draw.direction() as f32)),
+        Expr::Heading(..) => Ok(Value::F(draw.direction())),
         Expr::Color(..) => Ok(Value::F(draw.color() as f32)),
+        Expr::PenSize(..) => Ok(Value::F(draw.pen_size())),
+        Expr::FillColor(..) => Ok(Value::F(draw.fill_color() as f32)),
+        Expr::RepCount(end, len) => match variable.repcount() {
+            Some(count) => Ok(Value::F(count)),
+            None => Err(match_err(
+                sentence.to_string(),
+                line,
+                "UnDefinedRepCount".to_string(),
+                *end,
+                *len,
+            )),
+        },
+        Expr::Call(name, args, end, len) => {
+            match process_call(name, args, *end, *len, line, variable, draw, function, file)? {
+                Flow::Output(value) => Ok(value),
+                _ => Err(match_err(
+                    sentence.to_string(),
+                    line,
+                    "MissingOutput".to_string(),
+                    *end,
+                    *len,
+                )),
+            }
+        }
+        Expr::Sin(value, end, len)
+        | Expr::Cos(value, end, len)
+        | Expr::Tan(value, end, len)
+        | Expr::ArcTan(value, end, len)
+        | Expr::Sqrt(value, end, len)
+        | Expr::Abs(value, end, len)
+        | Expr::Round(value, end, len)
+        | Expr::Int(value, end, len) => {
+            let num = match process_expr(value, variable, line, sentence, draw, function, file)? {
+                Value::F(num) => num,
+                Value::B(_) => {
+                    let (end, len) = get_end_len(value);
+                    return Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "UnexpectedBooleanType".to_string(),
+                        end,
+                        len,
+                    ));
+                }
+            };
+
+            match expr {
+                Expr::Sin(..) => Ok(Value::F(num.to_radians().sin())),
+                Expr::Cos(..) => Ok(Value::F(num.to_radians().cos())),
+                Expr::Tan(..) => Ok(Value::F(num.to_radians().tan())),
+                Expr::ArcTan(..) => Ok(Value::F(num.atan().to_degrees())),
+                Expr::Sqrt(..) if num < 0.0 => Err(match_err(
+                    sentence.to_string(),
+                    line,
+                    "NegativeSquareRoot".to_string(),
+                    *end,
+                    *len,
+                )),
+                Expr::Sqrt(..) => Ok(Value::F(num.sqrt())),
+                Expr::Abs(..) => Ok(Value::F(num.abs())),
+                Expr::Round(..) => Ok(Value::F(num.round())),
+                _ => Ok(Value::F(num.trunc())),
+            }
+        }
+        Expr::Power(expr1, expr2, end, len)
+        | Expr::Mod(expr1, expr2, end, len)
+        | Expr::Towards(expr1, expr2, end, len) => {
+            match (
+                process_expr(expr1, variable, line, sentence, draw, function, file)?,
+                process_expr(expr2, variable, line, sentence, draw, function, file)?,
+            ) {
+                (Value::F(num1), Value::F(num2)) => match expr {
+                    Expr::Power(..) if !num1.powf(num2).is_finite() => Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "InvalidPower".to_string(),
+                        *end,
+                        *len,
+                    )),
+                    Expr::Power(..) => Ok(Value::F(num1.powf(num2))),
+                    Expr::Towards(..) => Ok(Value::F(draw.towards(num1, num2))),
+                    _ if num2 == 0.0 => Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "ModByZero".to_string(),
+                        *end,
+                        *len,
+                    )),
+                    _ => Ok(Value::F(num1 % num2)),
+                },
+                (Value::B(_), _) => {
+                    let (end, len) = get_end_len(expr1);
+                    Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "UnexpectedBooleanType".to_string(),
+                        end,
+                        len,
+                    ))
+                }
+                _ => {
+                    let (end, len) = get_end_len(expr2);
+                    Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "UnexpectedBooleanType".to_string(),
+                        end,
+                        len,
+                    ))
+                }
+            }
+        }
+        Expr::Random(value, end, len) => {
+            match process_expr(value, variable, line, sentence, draw, function, file)? {
+                Value::F(num) if num >= 1.0 && num.fract() == 0.0 => {
+                    Ok(Value::F(variable.random().below(num as u64) as f32))
+                }
+                Value::F(_) => Err(match_err(
+                    sentence.to_string(),
+                    line,
+                    "InvalidRandomRange".to_string(),
+                    *end,
+                    *len,
+                )),
+                Value::B(_) => {
+                    let (end, len) = get_end_len(value);
+                    Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "UnexpectedBooleanType".to_string(),
+                        end,
+                        len,
+                    ))
+                }
+            }
+        }
+        Expr::RandomFloat(..) => Ok(Value::F(variable.random().float())),
+        Expr::Pick(args, ..) => {
+            let idx = variable.random().below(args.len() as u64) as usize;
+            process_expr(&args[idx], variable, line, sentence, draw, function, file)
+        }
         _ => unreachable!(),
     }
 }
@@ -304,6 +464,24 @@ pub fn get_end_len(expr: &Expr) -> (usize, usize) {
         | Expr::YCor(end, len)
         | Expr::Heading(end, len)
         | Expr::Color(end, len)
+        | Expr::PenSize(end, len)
+        | Expr::FillColor(end, len)
+        | Expr::RepCount(end, len)
+        | Expr::Call(.., end, len)
+        | Expr::Sin(.., end, len)
+        | Expr::Cos(.., end, len)
+        | Expr::Tan(.., end, len)
+        | Expr::ArcTan(.., end, len)
+        | Expr::Sqrt(.., end, len)
+        | Expr::Abs(.., end, len)
+        | Expr::Round(.., end, len)
+        | Expr::Int(.., end, len)
+        | Expr::Power(.., end, len)
+        | Expr::Mod(.., end, len)
+        | Expr::Towards(.., end, len)
+        | Expr::Random(.., end, len)
+        | Expr::RandomFloat(end, len)
+        | Expr::Pick(.., end, len)
         | Expr::Eq(.., end, len)
         | Expr::Ne(.., end, len)
         | Expr::Lt(.., end, len)
@@ -0,0 +1,374 @@
+use resvg::usvg::{fontdb, NodeExt, TreeTextToPath, TreeWriting, XmlOptions};
+use resvg::{tiny_skia, usvg};
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::OnceLock;
+use unsvg::Color;
+
+use crate::generation::display::{arc_curves, DisplayList, Shape};
+
+pub struct Image {
+    tree: usvg::Tree,
+    /// The `<text>` element of each label, written in place of its group since usvg can only
+    /// write text as outlines.
+    labels: Vec<String>,
+}
+
+pub fn hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.red, color.green, color.blue)
+}
+
+pub fn quantize(x: f32) -> f32 {
+    (x * 256.0).round() / 256.0
+}
+
+pub fn get_end_coordinates(x: f32, y: f32, direction: f32, length: f32) -> (f32, f32) {
+    let direction_rad = (direction.rem_euclid(360.0) - 90.0).to_radians();
+
+    (
+        quantize(quantize(x) + direction_rad.cos() * length),
+        quantize(quantize(y) + direction_rad.sin() * length),
+    )
+}
+
+/// Writes a label as an SVG `<text>` element, turned about its start to face the heading.
+pub fn text_element(
+    text: &str,
+    (x, y): (f32, f32),
+    direction: f32,
+    height: f32,
+    color: Color,
+) -> String {
+    let text = text
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    let (x, y) = (quantize(x), quantize(y));
+    let angle = quantize((direction - 90.0).rem_euclid(360.0));
+    format!(
+        "<text x=\"{x}\" y=\"{y}\" font-family=\"sans-serif\" font-size=\"{height}\" \
+         fill=\"{}\" transform=\"rotate({angle} {x} {y})\">{text}</text>",
+        hex(color),
+    )
+}
+
+fn load_fonts() -> &'static fontdb::Database {
+    static FONTS: OnceLock<fontdb::Database> = OnceLock::new();
+    FONTS.get_or_init(load_system_fonts)
+}
+
+fn load_system_fonts() -> fontdb::Database {
+    let mut fontdb = fontdb::Database::new();
+    fontdb.load_system_fonts();
+
+    let query = fontdb::Query {
+        families: &[fontdb::Family::SansSerif],
+        ..fontdb::Query::default()
+    };
+    let fallback = match fontdb.query(&query) {
+        Some(_) => None,
+        None => fontdb.faces().next().map(|face| face.families[0].0.clone()),
+    };
+    if let Some(family) = fallback {
+        fontdb.set_sans_serif_family(family);
+    }
+    fontdb
+}
+
+impl Image {
+    pub fn render(display: &DisplayList) -> Result<Self, String> {
+        let mut image = Self::overlay(display.view, [])?;
+        let rect = image.tree.view_box.rect;
+        let mut path = usvg::Path::new(Rc::new(tiny_skia::PathBuilder::from_rect(rect.to_rect())));
+        path.fill = Some(usvg::Fill::from_paint(usvg::Paint::Color(
+            display.background,
+        )));
+        image.tree.root.append_kind(usvg::NodeKind::Path(path));
+
+        for shape in &display.shapes {
+            image.draw(shape)?;
+        }
+        Ok(image)
+    }
+
+    /// Draws only `shapes`, without a background, to be painted over an image drawn before.
+    pub fn overlay<'a>(
+        (x, y, width, height): (f32, f32, f32, f32),
+        shapes: impl IntoIterator<Item = &'a Shape>,
+    ) -> Result<Self, String> {
+        let size = usvg::Size::from_wh(width, height).ok_or("Invalid image size".to_string())?;
+        let tree = usvg::Tree {
+            size,
+            view_box: usvg::ViewBox {
+                rect: size.to_non_zero_rect(x, y),
+                aspect: usvg::AspectRatio::default(),
+            },
+            root: usvg::Node::new(usvg::NodeKind::Group(usvg::Group::default())),
+        };
+
+        let mut image = Self {
+            tree,
+            labels: Vec::new(),
+        };
+        for shape in shapes {
+            image.draw(shape)?;
+        }
+        Ok(image)
+    }
+
+    fn draw(&mut self, shape: &Shape) -> Result<(), String> {
+        match shape {
+            Shape::Line {
+                start,
+                end,
+                color,
+                width,
+            } => self.draw_line(*start, *end, *color, *width),
+            Shape::Arc {
+                start,
+                center,
+                radius,
+                angle,
+                color,
+                width,
+                ..
+            } => self.draw_arc(*start, *center, *radius, *angle, *color, *width),
+            Shape::Circle {
+                center,
+                radius,
+                color,
+                width,
+            } => self.draw_circle(*center, *radius, *color, *width),
+            Shape::Fill { points, color } => self.fill_polygon(points, *color),
+            Shape::Label {
+                text,
+                position,
+                direction,
+                height,
+                color,
+            } => self.draw_label(text, *position, *direction, *height, *color),
+        }
+    }
+
+    fn stroke(&mut self, path: tiny_skia::Path, color: Color, width: f32) -> Result<(), String> {
+        let mut path = usvg::Path::new(Rc::new(path));
+        let stroke = usvg::Stroke {
+            paint: usvg::Paint::Color(color),
+            width: usvg::StrokeWidth::new(width).ok_or("Invalid pen size".to_string())?,
+            ..usvg::Stroke::default()
+        };
+        path.stroke = Some(stroke);
+        self.tree.root.append_kind(usvg::NodeKind::Path(path));
+
+        Ok(())
+    }
+
+    fn draw_line(
+        &mut self,
+        (x1, y1): (f32, f32),
+        (x2, y2): (f32, f32),
+        color: Color,
+        width: f32,
+    ) -> Result<(), String> {
+        let mut path = tiny_skia::PathBuilder::new();
+        path.move_to(quantize(x1), quantize(y1));
+        path.line_to(quantize(x2), quantize(y2));
+
+        let path = path.finish().ok_or("Could not draw line".to_string())?;
+        self.stroke(path, color, width)
+    }
+
+    fn fill_polygon(&mut self, points: &[(f32, f32)], color: Color) -> Result<(), String> {
+        let mut path = tiny_skia::PathBuilder::new();
+        for (i, &(x, y)) in points.iter().enumerate() {
+            if i == 0 {
+                path.move_to(quantize(x), quantize(y));
+            } else {
+                path.line_to(quantize(x), quantize(y));
+            }
+        }
+        path.close();
+
+        let mut path = usvg::Path::new(Rc::new(
+            path.finish().ok_or("Could not fill polygon".to_string())?,
+        ));
+        path.fill = Some(usvg::Fill::from_paint(usvg::Paint::Color(color)));
+
+        self.tree.root.append_kind(usvg::NodeKind::Path(path));
+
+        Ok(())
+    }
+
+    /// Adds an arc to `path` as cubic Béziers, from the point at angle `from` on the circle.
+    fn arc_to(
+        path: &mut tiny_skia::PathBuilder,
+        center: (f32, f32),
+        radius: f32,
+        from: f32,
+        angle: f32,
+    ) {
+        for [(x1, y1), (x2, y2), (x, y)] in arc_curves(center, radius, from, angle) {
+            path.cubic_to(
+                quantize(x1),
+                quantize(y1),
+                quantize(x2),
+                quantize(y2),
+                quantize(x),
+                quantize(y),
+            );
+        }
+    }
+
+    fn draw_arc(
+        &mut self,
+        (x, y): (f32, f32),
+        (cx, cy): (f32, f32),
+        radius: f32,
+        angle: f32,
+        color: Color,
+        width: f32,
+    ) -> Result<(), String> {
+        let mut path = tiny_skia::PathBuilder::new();
+        path.move_to(quantize(x), quantize(y));
+        let from = (y - cy).atan2(x - cx).to_degrees();
+        Self::arc_to(&mut path, (cx, cy), radius, from, angle);
+
+        let path = path.finish().ok_or("Could not draw arc".to_string())?;
+        self.stroke(path, color, width)
+    }
+
+    fn draw_circle(
+        &mut self,
+        (x, y): (f32, f32),
+        radius: f32,
+        color: Color,
+        width: f32,
+    ) -> Result<(), String> {
+        let mut path = tiny_skia::PathBuilder::new();
+        path.move_to(quantize(x + radius), quantize(y));
+        Self::arc_to(&mut path, (x, y), radius, 0.0, 360.0);
+        path.close();
+
+        let path = path.finish().ok_or("Could not draw circle".to_string())?;
+        self.stroke(path, color, width)
+    }
+
+    /// Draws a label as text in a group of its own, which `save_svg` replaces with a `<text>`
+    /// element and `pixmap` fills with the outlines of its glyphs.
+    fn draw_label(
+        &mut self,
+        text: &str,
+        (x, y): (f32, f32),
+        direction: f32,
+        height: f32,
+        color: Color,
+    ) -> Result<(), String> {
+        let element = text_element(text, (x, y), direction, height, color);
+        let (x, y) = (quantize(x), quantize(y));
+        let angle = quantize((direction - 90.0).rem_euclid(360.0));
+        let span = usvg::TextSpan {
+            start: 0,
+            end: text.len(),
+            fill: Some(usvg::Fill::from_paint(usvg::Paint::Color(color))),
+            stroke: None,
+            paint_order: usvg::PaintOrder::default(),
+            font: usvg::Font {
+                families: vec!["sans-serif".to_string()],
+                style: usvg::FontStyle::default(),
+                stretch: usvg::FontStretch::default(),
+                weight: 400,
+            },
+            font_size: usvg::NonZeroPositiveF32::new(height)
+                .ok_or("Invalid label height".to_string())?,
+            small_caps: false,
+            apply_kerning: true,
+            decoration: usvg::TextDecoration {
+                underline: None,
+                overline: None,
+                line_through: None,
+            },
+            dominant_baseline: usvg::DominantBaseline::default(),
+            alignment_baseline: usvg::AlignmentBaseline::default(),
+            baseline_shift: Vec::new(),
+            visibility: usvg::Visibility::Visible,
+            letter_spacing: 0.0,
+            word_spacing: 0.0,
+            text_length: None,
+            length_adjust: usvg::LengthAdjust::default(),
+        };
+        let text = usvg::Text {
+            id: String::new(),
+            transform: usvg::Transform::from_rotate_at(angle, x, y),
+            rendering_mode: usvg::TextRendering::default(),
+            positions: Vec::new(),
+            rotate: Vec::new(),
+            writing_mode: usvg::WritingMode::LeftToRight,
+            chunks: vec![usvg::TextChunk {
+                x: Some(x),
+                y: Some(y),
+                anchor: usvg::TextAnchor::Start,
+                spans: vec![span],
+                text_flow: usvg::TextFlow::Linear,
+                text: text.to_string(),
+            }],
+        };
+
+        let group = self
+            .tree
+            .root
+            .append_kind(usvg::NodeKind::Group(usvg::Group {
+                id: format!("label{}", self.labels.len()),
+                ..usvg::Group::default()
+            }));
+        group.append_kind(usvg::NodeKind::Text(text));
+        self.labels.push(element);
+        Ok(())
+    }
+
+    /// Replaces the text of every label with the outlines of its glyphs, which is all resvg
+    /// can draw.
+    fn outline_labels(&mut self) -> Result<(), String> {
+        if !self.labels.is_empty() && load_fonts().is_empty() {
+            return Err("No font was found to draw labels with".to_string());
+        }
+        self.tree.convert_text(load_fonts());
+        Ok(())
+    }
+
+    pub fn pixmap(&mut self, scale: f32) -> Result<tiny_skia::Pixmap, String> {
+        let size = self
+            .tree
+            .size
+            .to_int_size()
+            .scale_by(scale)
+            .ok_or("Invalid image size".to_string())?;
+        let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+            .ok_or("Could not create image".to_string())?;
+        self.paint(&mut pixmap)?;
+        Ok(pixmap)
+    }
+
+    /// Paints the image over what `pixmap` already shows, scaled to fill it.
+    pub fn paint(&mut self, pixmap: &mut tiny_skia::Pixmap) -> Result<(), String> {
+        self.outline_labels()?;
+        let tree = resvg::Tree::from_usvg(&self.tree);
+        let transform = tiny_skia::Transform::from_scale(
+            pixmap.width() as f32 / tree.size.width(),
+            pixmap.height() as f32 / tree.size.height(),
+        );
+        tree.render(transform, &mut pixmap.as_mut());
+        Ok(())
+    }
+
+    pub fn save_png<P: AsRef<Path>>(&mut self, path: P) -> Result<(), String> {
+        self.pixmap(1.0)?.save_png(path).map_err(|e| e.to_string())
+    }
+
+    pub fn save_svg<P: AsRef<Path>>(&self, path: P) -> Result<(), String> {
+        let mut svg = self.tree.to_string(&XmlOptions::default());
+        for (i, label) in self.labels.iter().enumerate() {
+            svg = svg.replacen(&format!("<g id=\"label{i}\"/>"), label, 1);
+        }
+        std::fs::write(path, svg).map_err(|e| e.to_string())
+    }
+}
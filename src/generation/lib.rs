@@ -1,21 +1,192 @@
 use crate::ast::structs::Function;
-use crate::generation::{draw::Draw, err::GenerationError, stmt::process_stmt, variable::Variable};
-use unsvg::Image;
+use crate::generation::{
+    display::DisplayList,
+    draw::{Canvas, Draw},
+    err::GenerationError,
+    stmt::process_stmt,
+    variable::{Limit, Variable},
+};
 
 pub fn code_generation(
     ast: Function,
     file: &[String],
-    width: u32,
-    height: u32,
-) -> Result<Image, GenerationError<'static>> {
-    let mut image = Image::new(width, height);
-    let mut draw = Draw::new(width as f32, height as f32, &mut image);
-    let mut variable = Variable::new();
-
-    ast.get_args()
-        .iter()
-        .for_each(|arg| variable.insert_num(arg.to_string(), None));
+    canvas: &Canvas,
+    limit: Limit,
+    seed: u64,
+) -> Result<DisplayList, GenerationError<'static>> {
+    let mut display = DisplayList::new(canvas.width, canvas.height);
+    if canvas.animate {
+        display.start_recording();
+    }
+    let mut draw = Draw::new(canvas, &mut display);
+    let mut variable = Variable::new(limit, seed);
+
     process_stmt(ast.get_main(), &mut variable, &mut draw, &ast, file)?;
+    draw.finish();
+
+    if let Some(margin) = canvas.auto_size {
+        display.fit(margin);
+    }
+
+    Ok(display)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::parse_ast;
+    use crate::generation::display::Shape;
+    use unsvg::COLORS;
+
+    fn run(program: &str) -> DisplayList {
+        let file = program.lines().map(String::from).collect::<Vec<_>>();
+        let ast = parse_ast(&file).expect("Failed to parse the program");
+        code_generation(ast, &file, &Canvas::new(200, 200), Limit::default(), 1)
+            .expect("Failed to run the program")
+    }
+
+    #[test]
+    fn lines_follow_the_turtle() {
+        let display = run(r#"PENDOWN
+FORWARD "10
+TURN "90
+SETPENSIZE "3
+SETPENCOLOR "1
+FORWARD "20
+PENUP
+FORWARD "20
+"#);
+
+        assert_eq!(
+            display.shapes,
+            vec![
+                Shape::Line {
+                    start: (100.0, 100.0),
+                    end: (100.0, 90.0),
+                    color: COLORS[7],
+                    width: 1.0,
+                },
+                Shape::Line {
+                    start: (100.0, 90.0),
+                    end: (120.0, 90.0),
+                    color: COLORS[1],
+                    width: 3.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn arcs_past_a_full_turn_add_a_circle() {
+        let display = run(r#"PENDOWN
+ARC "90 "20
+ARC "-450 "10
+"#);
+
+        assert_eq!(
+            display.shapes,
+            vec![
+                Shape::Arc {
+                    start: (100.0, 100.0),
+                    end: (120.0, 80.0),
+                    center: (120.0, 100.0),
+                    radius: 20.0,
+                    angle: 90.0,
+                    color: COLORS[7],
+                    width: 1.0,
+                },
+                Shape::Circle {
+                    center: (120.0, 70.0),
+                    radius: 10.0,
+                    color: COLORS[7],
+                    width: 1.0,
+                },
+                Shape::Arc {
+                    start: (120.0, 80.0),
+                    end: (130.0, 70.0),
+                    center: (120.0, 70.0),
+                    radius: 10.0,
+                    angle: -90.0,
+                    color: COLORS[7],
+                    width: 1.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn fills_go_under_their_outline() {
+        let display = run(r#"SETFILLCOLOR "4
+PENDOWN
+BEGINFILL
+FORWARD "10
+TURN "90
+FORWARD "10
+ENDFILL
+"#);
+
+        assert_eq!(
+            display.shapes[0],
+            Shape::Fill {
+                points: vec![(100.0, 100.0), (100.0, 90.0), (110.0, 90.0)],
+                color: COLORS[4],
+            }
+        );
+        assert_eq!(display.len(), 3);
+    }
+
+    #[test]
+    fn wrapped_lines_continue_on_the_opposite_edge() {
+        let display = run(r#"WRAP
+PENDOWN
+FORWARD "150
+"#);
+
+        assert_eq!(
+            display.shapes,
+            vec![
+                Shape::Line {
+                    start: (100.0, 100.0),
+                    end: (100.0, 0.0),
+                    color: COLORS[7],
+                    width: 1.0,
+                },
+                Shape::Line {
+                    start: (100.0, 200.0),
+                    end: (100.0, 150.0),
+                    color: COLORS[7],
+                    width: 1.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn labels_keep_the_turtle_heading_and_height() {
+        let display = run(r#"SETLABELHEIGHT "16
+SETHEADING "90
+LABEL "Logo
+"#);
+
+        assert_eq!(
+            display.shapes,
+            vec![Shape::Label {
+                text: "Logo".to_string(),
+                position: (100.0, 100.0),
+                direction: 90.0,
+                height: 16.0,
+                color: COLORS[7],
+            }]
+        );
+    }
+
+    #[test]
+    fn auto_size_fits_the_drawing() {
+        let mut display = run(r#"PENDOWN
+ARC "180 "20
+"#);
+        display.fit(5.0);
 
-    Ok(image)
+        assert_eq!(display.view, (94.0, 74.0, 52.0, 32.0));
+    }
 }
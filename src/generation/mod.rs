@@ -1,8 +1,22 @@
+pub use animation::Animation;
+pub use draw::{Boundary, Canvas};
+pub use image::Image;
 pub use lib::code_generation;
+pub use plotter::Plotter;
+pub use preview::preview;
+pub use variable::{Limit, DEFAULT_MAX_DEPTH, DEFAULT_MAX_STEPS};
+pub use vector::{save_eps, save_pdf};
 
+mod animation;
+mod display;
 mod draw;
 mod err;
 mod expr;
+mod image;
 mod lib;
+mod plotter;
+mod preview;
+mod random;
 mod stmt;
 mod variable;
+mod vector;
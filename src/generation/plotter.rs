@@ -0,0 +1,203 @@
+use std::path::Path;
+use unsvg::Color;
+
+use crate::generation::display::{DisplayList, Shape};
+use crate::generation::image::hex;
+
+/// Degrees of an arc covered by each straight move when plotting it.
+const ARC_STEP: f32 = 5.0;
+/// HPGL plotter units in a millimetre.
+const HPGL_UNITS: f32 = 40.0;
+
+/// Settings for pen plotter and CNC output. Distances are in millimetres.
+pub struct Plotter {
+    pub feed_rate: f32,
+    pub scale: f32,
+    pub pen_up: String,
+    pub pen_down: String,
+    pub optimize: bool,
+}
+
+/// A run of connected pen-down moves in one colour.
+struct Stroke {
+    color: Color,
+    points: Vec<(f32, f32)>,
+}
+
+fn arc_points((cx, cy): (f32, f32), radius: f32, from: f32, angle: f32) -> Vec<(f32, f32)> {
+    let steps = (angle.abs() / ARC_STEP).ceil().max(1.0) as usize;
+    (0..=steps)
+        .map(|step| {
+            let (sin, cos) = (from + angle * step as f32 / steps as f32)
+                .to_radians()
+                .sin_cos();
+            (cx + radius * cos, cy + radius * sin)
+        })
+        .collect()
+}
+
+fn distance((x1, y1): (f32, f32), (x2, y2): (f32, f32)) -> f32 {
+    (x2 - x1).hypot(y2 - y1)
+}
+
+/// Joins the drawn lines and arcs into strokes. Fills and labels cannot be drawn with a pen,
+/// so they are left out.
+fn strokes(display: &DisplayList) -> Vec<Stroke> {
+    let mut strokes: Vec<Stroke> = Vec::new();
+    for shape in &display.shapes {
+        let (points, color) = match shape {
+            Shape::Line {
+                start, end, color, ..
+            } => (vec![*start, *end], *color),
+            Shape::Arc {
+                start,
+                center,
+                radius,
+                angle,
+                color,
+                ..
+            } => {
+                let from = (start.1 - center.1).atan2(start.0 - center.0).to_degrees();
+                (arc_points(*center, *radius, from, *angle), *color)
+            }
+            Shape::Circle {
+                center,
+                radius,
+                color,
+                ..
+            } => (arc_points(*center, *radius, 0.0, 360.0), *color),
+            Shape::Fill { .. } | Shape::Label { .. } => continue,
+        };
+
+        match strokes.last_mut() {
+            Some(stroke) if stroke.color == color && stroke.points.last() == points.first() => {
+                stroke.points.extend(&points[1..]);
+            }
+            _ => strokes.push(Stroke { color, points }),
+        }
+    }
+    strokes
+}
+
+/// Groups strokes by colour, then greedily plots whichever stroke starts or ends closest to
+/// the pen, reversing it if needed.
+fn optimize(strokes: Vec<Stroke>, start: (f32, f32)) -> Vec<Stroke> {
+    let mut colors: Vec<Color> = Vec::new();
+    for stroke in &strokes {
+        if !colors.contains(&stroke.color) {
+            colors.push(stroke.color);
+        }
+    }
+
+    let mut remaining = strokes;
+    let mut ordered = Vec::with_capacity(remaining.len());
+    let mut pen = start;
+    for color in colors {
+        let mut group = Vec::new();
+        let mut rest = Vec::new();
+        for stroke in remaining {
+            match stroke.color == color {
+                true => group.push(stroke),
+                false => rest.push(stroke),
+            }
+        }
+        remaining = rest;
+
+        while !group.is_empty() {
+            let (index, reverse) = group
+                .iter()
+                .enumerate()
+                .flat_map(|(index, stroke)| {
+                    let start = distance(pen, stroke.points[0]);
+                    let end = distance(pen, stroke.points[stroke.points.len() - 1]);
+                    [(index, false, start), (index, true, end)]
+                })
+                .min_by(|a, b| a.2.total_cmp(&b.2))
+                .map(|(index, reverse, _)| (index, reverse))
+                .unwrap_or_default();
+
+            let mut stroke = group.swap_remove(index);
+            if reverse {
+                stroke.points.reverse();
+            }
+            pen = stroke.points[stroke.points.len() - 1];
+            ordered.push(stroke);
+        }
+    }
+    ordered
+}
+
+impl Plotter {
+    fn strokes(&self, display: &DisplayList) -> Vec<Stroke> {
+        let strokes = strokes(display);
+        let (left, top, _, height) = display.view;
+        match self.optimize {
+            true => optimize(strokes, (left, top + height)),
+            false => strokes,
+        }
+    }
+
+    /// Converts a point on the canvas to millimetres from the bottom left corner.
+    fn position(&self, display: &DisplayList, (x, y): (f32, f32)) -> (f32, f32) {
+        let (left, top, _, height) = display.view;
+        ((x - left) * self.scale, (top + height - y) * self.scale)
+    }
+
+    pub fn save_hpgl<P: AsRef<Path>>(&self, display: &DisplayList, path: P) -> Result<(), String> {
+        // HPGL velocity is in centimetres per second.
+        let mut hpgl = format!("IN;\nVS{};\n", self.feed_rate / 600.0);
+        let mut pens: Vec<Color> = Vec::new();
+        let mut current = 0;
+
+        for stroke in self.strokes(display) {
+            let pen = match pens.iter().position(|&color| color == stroke.color) {
+                Some(index) => index + 1,
+                None => {
+                    pens.push(stroke.color);
+                    pens.len()
+                }
+            };
+            if pen != current {
+                hpgl.push_str(&format!("SP{pen};\n"));
+                current = pen;
+            }
+
+            let points = stroke
+                .points
+                .iter()
+                .map(|&point| {
+                    let (x, y) = self.position(display, point);
+                    format!("{},{}", (x * HPGL_UNITS).round(), (y * HPGL_UNITS).round())
+                })
+                .collect::<Vec<_>>();
+            hpgl.push_str(&format!("PU{};\nPD{};\n", points[0], points[1..].join(",")));
+        }
+
+        hpgl.push_str("PU;\nSP0;\n");
+        std::fs::write(path, hpgl).map_err(|e| e.to_string())
+    }
+
+    pub fn save_gcode<P: AsRef<Path>>(&self, display: &DisplayList, path: P) -> Result<(), String> {
+        // The feed rate is modal, set it before the first `G1`, which may be a pen-down move.
+        let mut gcode = format!("G21\nG90\nF{}\n{}\n", self.feed_rate, self.pen_up);
+        let mut color = None;
+
+        for stroke in self.strokes(display) {
+            if color != Some(stroke.color) {
+                gcode.push_str(&format!("; colour {}\n", hex(stroke.color)));
+                color = Some(stroke.color);
+            }
+
+            let (x, y) = self.position(display, stroke.points[0]);
+            gcode.push_str(&format!("G0 X{x:.3} Y{y:.3}\n{}\n", self.pen_down));
+            for &point in &stroke.points[1..] {
+                let (x, y) = self.position(display, point);
+                gcode.push_str(&format!("G1 X{x:.3} Y{y:.3}\n"));
+            }
+            gcode.push_str(&format!("{}\n", self.pen_up));
+        }
+
+        gcode.push_str("G0 X0 Y0\n");
+        std::fs::write(path, gcode).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,75 @@
+use unsvg::{Color, COLORS};
+
+use crate::generation::display::DisplayList;
+use crate::generation::image::Image;
+
+/// The ANSI foreground colour closest to each colour in `unsvg::COLORS`.
+const ANSI: [u8; 16] = [
+    30, 94, 96, 92, 91, 95, 93, 97, 31, 37, 32, 36, 91, 35, 33, 90,
+];
+/// The braille dot bit for each pixel of a 2 by 4 cell, indexed by row then column.
+const DOTS: [[u32; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+/// How far a pixel has to be from the background to count as drawn.
+const THRESHOLD: u32 = 48;
+
+fn difference(a: Color, b: Color) -> u32 {
+    a.red.abs_diff(b.red) as u32 + a.green.abs_diff(b.green) as u32 + a.blue.abs_diff(b.blue) as u32
+}
+
+fn ansi(color: Color) -> u8 {
+    COLORS
+        .iter()
+        .zip(ANSI)
+        .min_by_key(|(palette, _)| difference(**palette, color))
+        .map(|(_, code)| code)
+        .unwrap_or(ANSI[7])
+}
+
+/// Renders the drawing as braille characters, each covering 2 by 4 pixels of an image scaled to
+/// the given number of columns, coloured with the drawn pixel that stands out most.
+pub fn preview(display: &DisplayList, columns: u32, color: bool) -> Result<String, String> {
+    let (_, _, width, _) = display.view;
+    let pixmap = Image::render(display)?.pixmap(columns as f32 * 2.0 / width)?;
+    let (width, height) = (pixmap.width(), pixmap.height());
+    let pixel = |x: u32, y: u32| {
+        pixmap
+            .pixel(x, y)
+            .map(|p| Color::new_rgb(p.red(), p.green(), p.blue()))
+            .unwrap_or(display.background)
+    };
+
+    let mut text = String::new();
+    for row in (0..height).step_by(4) {
+        let mut current = None;
+        for column in (0..width).step_by(2) {
+            let mut dots = 0;
+            let mut strongest = (0, display.background);
+            for (dy, bits) in DOTS.iter().enumerate() {
+                for (dx, bit) in bits.iter().enumerate() {
+                    let color = pixel(column + dx as u32, row + dy as u32);
+                    let distance = difference(color, display.background);
+                    if distance >= THRESHOLD {
+                        dots |= bit;
+                    }
+                    if distance > strongest.0 {
+                        strongest = (distance, color);
+                    }
+                }
+            }
+
+            if color && dots != 0 {
+                let code = ansi(strongest.1);
+                if current != Some(code) {
+                    text.push_str(&format!("\x1b[{code}m"));
+                    current = Some(code);
+                }
+            }
+            text.push(char::from_u32(0x2800 + dots).unwrap_or(' '));
+        }
+        if color && current.is_some() {
+            text.push_str("\x1b[0m");
+        }
+        text.push('\n');
+    }
+    Ok(text)
+}
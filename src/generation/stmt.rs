@@ -1,24 +1,42 @@
-use crate::ast::structs::{Assign, DeclName, Function, Stmt};
+use crate::ast::structs::{Assign, DeclName, Expr, Function, Stmt};
 
 use crate::generation::{
-    draw::Draw,
+    draw::{Boundary, Draw},
     err::{match_err, GenerationError},
     expr::{get_end_len, process_expr, Value},
     variable::{Type, Variable},
 };
 
+pub enum Flow {
+    Continue,
+    Stop,
+    Output(Value),
+}
+
 pub fn process_stmt(
     stmt_list: &Vec<Stmt>,
     variable: &mut Variable,
     draw: &mut Draw,
     function: &Function,
     file: &[String],
-) -> Result<(), GenerationError<'static>> {
+) -> Result<Flow, GenerationError<'static>> {
     for stmt in stmt_list {
+        variable.step();
         match stmt {
             Stmt::If(expr, stmt, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
-                    Value::B(true) => process_stmt(stmt, variable, draw, function, file)?,
+                match process_expr(
+                    expr.as_ref(),
+                    variable,
+                    *line,
+                    &file[*line],
+                    draw,
+                    function,
+                    file,
+                )? {
+                    Value::B(true) => match process_stmt(stmt, variable, draw, function, file)? {
+                        Flow::Continue => {}
+                        flow => return Ok(flow),
+                    },
                     Value::F(_) => {
                         let (end, len) = get_end_len(expr.as_ref());
                         return Err(match_err(
@@ -32,9 +50,50 @@ pub fn process_stmt(
                     _ => {}
                 }
             }
+            Stmt::IfElse(expr, then_stmt, else_stmt, line) => {
+                match process_expr(
+                    expr.as_ref(),
+                    variable,
+                    *line,
+                    &file[*line],
+                    draw,
+                    function,
+                    file,
+                )? {
+                    Value::B(bool) => {
+                        let stmt = if bool { then_stmt } else { else_stmt };
+                        match process_stmt(stmt, variable, draw, function, file)? {
+                            Flow::Continue => {}
+                            flow => return Ok(flow),
+                        }
+                    }
+                    Value::F(_) => {
+                        let (end, len) = get_end_len(expr.as_ref());
+                        return Err(match_err(
+                            file[*line].to_string(),
+                            *line,
+                            "UnexpectedNumberType".to_string(),
+                            end,
+                            len,
+                        ));
+                    }
+                }
+            }
             Stmt::While(expr, stmt, line) => loop {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
-                    Value::B(true) => process_stmt(stmt, variable, draw, function, file)?,
+                check_limit(variable, *line, file)?;
+                match process_expr(
+                    expr.as_ref(),
+                    variable,
+                    *line,
+                    &file[*line],
+                    draw,
+                    function,
+                    file,
+                )? {
+                    Value::B(true) => match process_stmt(stmt, variable, draw, function, file)? {
+                        Flow::Continue => {}
+                        flow => return Ok(flow),
+                    },
                     Value::F(_) => {
                         let (end, len) = get_end_len(expr.as_ref());
                         return Err(match_err(
@@ -48,8 +107,68 @@ pub fn process_stmt(
                     _ => break,
                 }
             },
+            Stmt::Repeat(expr, stmt, line) => {
+                let count = match process_expr(
+                    expr.as_ref(),
+                    variable,
+                    *line,
+                    &file[*line],
+                    draw,
+                    function,
+                    file,
+                )? {
+                    Value::B(_) => {
+                        let (end, len) = get_end_len(expr.as_ref());
+                        return Err(match_err(
+                            file[*line].to_string(),
+                            *line,
+                            "UnexpectedBooleanType".to_string(),
+                            end,
+                            len,
+                        ));
+                    }
+                    Value::F(num) => {
+                        if !num.is_finite() || num.fract() != 0.0 {
+                            let (end, len) = get_end_len(expr.as_ref());
+                            return Err(match_err(
+                                file[*line].to_string(),
+                                *line,
+                                "NonIntegerValueError".to_string(),
+                                end,
+                                len,
+                            ));
+                        }
+                        num.max(0.0) as usize
+                    }
+                };
+
+                variable.push_repcount();
+                for _ in 0..count {
+                    if let Err(e) = check_limit(variable, *line, file) {
+                        variable.pop_repcount();
+                        return Err(e);
+                    }
+                    variable.next_repcount();
+                    match process_stmt(stmt, variable, draw, function, file)? {
+                        Flow::Continue => {}
+                        flow => {
+                            variable.pop_repcount();
+                            return Ok(flow);
+                        }
+                    }
+                }
+                variable.pop_repcount();
+            }
             Stmt::Make(assign, expr, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
+                match process_expr(
+                    expr.as_ref(),
+                    variable,
+                    *line,
+                    &file[*line],
+                    draw,
+                    function,
+                    file,
+                )? {
                     Value::B(bool) => {
                         if let Assign::Var(name, ..) = assign.as_ref() {
                             variable.insert_bool(name.to_string(), Some(bool));
@@ -65,7 +184,7 @@ pub fn process_stmt(
             Stmt::AddAssign(assign, expr, line) => {
                 let org = match assign.as_ref() {
                     Assign::Var(name, end, len) => match variable.get(name) {
-                        Some(Some(Type::F(num))) => num,
+                        Some(Some(Type::F(num))) => *num,
                         Some(Some(Type::B(_))) => Err(match_err(
                             file[*line].to_string(),
                             *line,
@@ -91,7 +210,15 @@ pub fn process_stmt(
                     _ => unreachable!(),
                 };
 
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
+                match process_expr(
+                    expr.as_ref(),
+                    variable,
+                    *line,
+                    &file[*line],
+                    draw,
+                    function,
+                    file,
+                )? {
                     Value::B(_) => {
                         let (end, len) = get_end_len(expr.as_ref());
                         return Err(match_err(
@@ -112,67 +239,63 @@ pub fn process_stmt(
             Stmt::PenUp(..) => draw.pen_up(),
             Stmt::PenDown(..) => draw.pen_down(),
             Stmt::Forward(expr, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
-                    Value::B(_) => {
-                        let (end, len) = get_end_len(expr.as_ref());
-                        return Err(match_err(
-                            file[*line].to_string(),
-                            *line,
-                            "UnexpectedNumberType".to_string(),
-                            end,
-                            len,
-                        ));
-                    }
-                    Value::F(num) => draw.pen_move(0, num),
-                }
+                let distance = process_finite(
+                    expr,
+                    *line,
+                    "InvalidDistance",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+                check_move(draw.pen_move(0.0, distance), *line, file)?;
             }
             Stmt::Back(expr, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
-                    Value::B(_) => {
-                        let (end, len) = get_end_len(expr.as_ref());
-                        return Err(match_err(
-                            file[*line].to_string(),
-                            *line,
-                            "UnexpectedNumberType".to_string(),
-                            end,
-                            len,
-                        ));
-                    }
-                    Value::F(num) => draw.pen_move(180, num),
-                }
+                let distance = process_finite(
+                    expr,
+                    *line,
+                    "InvalidDistance",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+                check_move(draw.pen_move(180.0, distance), *line, file)?;
             }
             Stmt::Left(expr, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
-                    Value::B(_) => {
-                        let (end, len) = get_end_len(expr.as_ref());
-                        return Err(match_err(
-                            file[*line].to_string(),
-                            *line,
-                            "UnexpectedNumberType".to_string(),
-                            end,
-                            len,
-                        ));
-                    }
-                    Value::F(num) => draw.pen_move(-90, num),
-                }
+                let distance = process_finite(
+                    expr,
+                    *line,
+                    "InvalidDistance",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+                check_move(draw.pen_move(-90.0, distance), *line, file)?;
             }
             Stmt::Right(expr, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
-                    Value::B(_) => {
-                        let (end, len) = get_end_len(expr.as_ref());
-                        return Err(match_err(
-                            file[*line].to_string(),
-                            *line,
-                            "UnexpectedNumberType".to_string(),
-                            end,
-                            len,
-                        ));
-                    }
-                    Value::F(num) => draw.pen_move(90, num),
-                }
+                let distance = process_finite(
+                    expr,
+                    *line,
+                    "InvalidDistance",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+                check_move(draw.pen_move(90.0, distance), *line, file)?;
             }
             Stmt::SetPenColor(expr, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
+                match process_expr(
+                    expr.as_ref(),
+                    variable,
+                    *line,
+                    &file[*line],
+                    draw,
+                    function,
+                    file,
+                )? {
                     Value::B(_) => {
                         let (end, len) = get_end_len(expr.as_ref());
                         return Err(match_err(
@@ -195,7 +318,7 @@ pub fn process_stmt(
                             ));
                         }
 
-                        if !(0.0..=15.0).contains(&num) {
+                        if !(0.0..draw.palette_len() as f32).contains(&num) {
                             let (end, len) = get_end_len(expr.as_ref());
                             return Err(match_err(
                                 file[*line].to_string(),
@@ -210,8 +333,170 @@ pub fn process_stmt(
                     }
                 }
             }
+            Stmt::SetPenSize(expr, line) => {
+                let width = process_positive(
+                    expr,
+                    *line,
+                    "InvalidPenSize",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+
+                draw.set_pen_size(width);
+            }
+            Stmt::SetLabelHeight(expr, line) => {
+                let height = process_positive(
+                    expr,
+                    *line,
+                    "InvalidLabelHeight",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+
+                draw.set_label_height(height);
+            }
+            Stmt::Label(text, ..) => draw.label(text),
+            Stmt::SetFillColor(expr, line) => {
+                let max = (draw.palette_len() - 1) as f32;
+                let index = process_integer(
+                    expr,
+                    *line,
+                    max,
+                    "UnDefinedColor",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+
+                draw.set_fill_color(index as usize);
+            }
+            Stmt::PushTurtle(..) => draw.push_turtle(),
+            Stmt::PopTurtle(line) => {
+                if !draw.pop_turtle() {
+                    return Err(match_err(
+                        file[*line].to_string(),
+                        *line,
+                        "EmptyTurtleStack".to_string(),
+                        0,
+                        file[*line].trim_start().len(),
+                    ));
+                }
+            }
+            Stmt::BeginFill(line) => {
+                if !draw.begin_fill() {
+                    return Err(match_err(
+                        file[*line].to_string(),
+                        *line,
+                        "UnmatchedFill".to_string(),
+                        0,
+                        file[*line].trim_start().len(),
+                    ));
+                }
+            }
+            Stmt::EndFill(line) => {
+                if !draw.end_fill() {
+                    return Err(match_err(
+                        file[*line].to_string(),
+                        *line,
+                        "UnmatchedFill".to_string(),
+                        0,
+                        file[*line].trim_start().len(),
+                    ));
+                }
+            }
+            Stmt::SetBackground(expr, line) => {
+                let max = (draw.palette_len() - 1) as f32;
+                let index = process_integer(
+                    expr,
+                    *line,
+                    max,
+                    "UnDefinedColor",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+
+                draw.set_background(index as usize);
+            }
+            Stmt::SetBackgroundRgb(red, green, blue, line) => {
+                let mut rgb = [0; 3];
+                for (channel, expr) in rgb.iter_mut().zip([red, green, blue]) {
+                    *channel = process_integer(
+                        expr,
+                        *line,
+                        255.0,
+                        "InvalidRgbValue",
+                        variable,
+                        draw,
+                        function,
+                        file,
+                    )? as u8;
+                }
+
+                draw.set_background_rgb(rgb[0], rgb[1], rgb[2]);
+            }
+            Stmt::SetPenRgb(red, green, blue, line) => {
+                let mut rgb = [0; 3];
+                for (channel, expr) in rgb.iter_mut().zip([red, green, blue]) {
+                    *channel = process_integer(
+                        expr,
+                        *line,
+                        255.0,
+                        "InvalidRgbValue",
+                        variable,
+                        draw,
+                        function,
+                        file,
+                    )? as u8;
+                }
+
+                draw.set_pen_rgb(rgb[0], rgb[1], rgb[2]);
+            }
+            Stmt::SetPalette(index, red, green, blue, line) => {
+                let max = (draw.palette_len() - 1) as f32;
+                let index = process_integer(
+                    index,
+                    *line,
+                    max,
+                    "UnDefinedColor",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )? as usize;
+
+                let mut rgb = [0; 3];
+                for (channel, expr) in rgb.iter_mut().zip([red, green, blue]) {
+                    *channel = process_integer(
+                        expr,
+                        *line,
+                        255.0,
+                        "InvalidRgbValue",
+                        variable,
+                        draw,
+                        function,
+                        file,
+                    )? as u8;
+                }
+
+                draw.set_palette(index, rgb[0], rgb[1], rgb[2]);
+            }
             Stmt::Turn(expr, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
+                match process_expr(
+                    expr.as_ref(),
+                    variable,
+                    *line,
+                    &file[*line],
+                    draw,
+                    function,
+                    file,
+                )? {
                     Value::B(_) => {
                         let (end, len) = get_end_len(expr.as_ref());
                         return Err(match_err(
@@ -223,23 +508,66 @@ pub fn process_stmt(
                         ));
                     }
                     Value::F(num) => {
-                        if !num.is_finite() || num.fract() != 0.0 {
+                        if !num.is_finite() {
                             let (end, len) = get_end_len(expr.as_ref());
                             return Err(match_err(
                                 file[*line].to_string(),
                                 *line,
-                                "NonIntegerValueError".to_string(),
+                                "InvalidAngleValue".to_string(),
                                 end,
                                 len,
                             ));
                         }
 
-                        draw.turn(num as i32);
+                        draw.turn(num);
                     }
                 }
             }
+            Stmt::Arc(angle, radius, line) => {
+                let angle = process_finite(
+                    angle,
+                    *line,
+                    "InvalidAngleValue",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+                let radius = process_positive(
+                    radius,
+                    *line,
+                    "InvalidRadius",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+
+                check_move(draw.arc(angle, radius), *line, file)?;
+            }
+            Stmt::Circle(radius, line) => {
+                let radius = process_positive(
+                    radius,
+                    *line,
+                    "InvalidRadius",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+
+                check_move(draw.arc(360.0, radius), *line, file)?;
+            }
             Stmt::SetHeading(expr, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
+                match process_expr(
+                    expr.as_ref(),
+                    variable,
+                    *line,
+                    &file[*line],
+                    draw,
+                    function,
+                    file,
+                )? {
                     Value::B(_) => {
                         let (end, len) = get_end_len(expr.as_ref());
                         return Err(match_err(
@@ -251,107 +579,341 @@ pub fn process_stmt(
                         ));
                     }
                     Value::F(num) => {
-                        if !num.is_finite() || num.fract() != 0.0 {
+                        if !num.is_finite() {
                             let (end, len) = get_end_len(expr.as_ref());
                             return Err(match_err(
                                 file[*line].to_string(),
                                 *line,
-                                "NonIntegerValueError".to_string(),
+                                "InvalidAngleValue".to_string(),
                                 end,
                                 len,
                             ));
                         }
 
-                        draw.set_heading(num as i32);
+                        draw.set_heading(num);
                     }
                 }
             }
             Stmt::SetX(expr, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
-                    Value::B(_) => {
-                        let (end, len) = get_end_len(expr.as_ref());
-                        return Err(match_err(
-                            file[*line].to_string(),
-                            *line,
-                            "UnexpectedNumberType".to_string(),
-                            end,
-                            len,
-                        ));
-                    }
-                    Value::F(num) => draw.set_x(num),
-                }
+                let num = process_finite(
+                    expr,
+                    *line,
+                    "InvalidDistance",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+                check_move(draw.set_x(num), *line, file)?;
             }
             Stmt::SetY(expr, line) => {
-                match process_expr(expr.as_ref(), variable, *line, &file[*line], draw)? {
-                    Value::B(_) => {
-                        let (end, len) = get_end_len(expr.as_ref());
-                        return Err(match_err(
-                            file[*line].to_string(),
-                            *line,
-                            "UnexpectedNumberType".to_string(),
-                            end,
-                            len,
-                        ));
-                    }
-                    Value::F(num) => draw.set_y(num),
+                let num = process_finite(
+                    expr,
+                    *line,
+                    "InvalidDistance",
+                    variable,
+                    draw,
+                    function,
+                    file,
+                )?;
+                check_move(draw.set_y(num), *line, file)?;
+            }
+            Stmt::SetPos(x, y, line) => {
+                let mut pos = [0.0; 2];
+                for (value, expr) in pos.iter_mut().zip([x, y]) {
+                    *value = process_finite(
+                        expr,
+                        *line,
+                        "InvalidDistance",
+                        variable,
+                        draw,
+                        function,
+                        file,
+                    )?;
                 }
+
+                check_move(draw.set_pos(pos[0], pos[1]), *line, file)?;
             }
+            Stmt::Home(line) => check_move(draw.home(), *line, file)?,
+            Stmt::ClearScreen(..) => draw.clear_screen(),
+            Stmt::Window(..) => draw.set_boundary(Boundary::Window),
+            Stmt::Wrap(..) => draw.set_boundary(Boundary::Wrap),
+            Stmt::Fence(..) => draw.set_boundary(Boundary::Fence),
             Stmt::Func(name, args, line) => {
                 let (name, end, len) = match name.as_ref() {
                     DeclName::String(name, end, len) => (name, end, len),
                     _ => unreachable!(),
                 };
 
-                let func = match function.get(name) {
-                    Some(func) => func,
-                    None => {
-                        return Err(match_err(
-                            file[*line].to_string(),
-                            *line,
-                            "UnDefinedFunction".to_string(),
-                            *end,
-                            *len,
-                        ))
-                    }
-                };
-
-                if args.len() > func.args.len() {
+                process_call(
+                    name, args, *end, *len, *line, variable, draw, function, file,
+                )?;
+            }
+            Stmt::Output(expr, line) => {
+                let value = process_expr(
+                    expr.as_ref(),
+                    variable,
+                    *line,
+                    &file[*line],
+                    draw,
+                    function,
+                    file,
+                )?;
+                return Ok(Flow::Output(value));
+            }
+            Stmt::Stop(..) => return Ok(Flow::Stop),
+            Stmt::Rerandom(expr, line) => match process_expr(
+                expr.as_ref(),
+                variable,
+                *line,
+                &file[*line],
+                draw,
+                function,
+                file,
+            )? {
+                Value::F(num) if num.fract() == 0.0 => variable.random().reseed(num as i64 as u64),
+                Value::F(_) => {
+                    let (end, len) = get_end_len(expr.as_ref());
                     return Err(match_err(
                         file[*line].to_string(),
                         *line,
-                        "TooManyArguments".to_string(),
-                        *end,
-                        *len,
+                        "NonIntegerValueError".to_string(),
+                        end,
+                        len,
                     ));
-                } else if args.len() > func.args.len() {
+                }
+                Value::B(_) => {
+                    let (end, len) = get_end_len(expr.as_ref());
                     return Err(match_err(
                         file[*line].to_string(),
                         *line,
-                        "MissingArguments".to_string(),
-                        *end,
-                        *len,
+                        "UnexpectedBooleanType".to_string(),
+                        end,
+                        len,
                     ));
                 }
-
-                for (arg, value) in func.args.iter().zip(args.iter()) {
-                    match process_expr(value, variable, *line, &file[*line], draw)? {
-                        Value::F(num) => {
-                            if let Assign::Var(name, ..) = &arg {
-                                variable.insert_num(name.to_string(), Some(num));
-                            }
-                        }
-                        Value::B(bool) => {
-                            if let Assign::Var(name, ..) = &arg {
-                                variable.insert_bool(name.to_string(), Some(bool));
-                            }
-                        }
-                    }
+            },
+            Stmt::Local(assign, ..) => {
+                if let Assign::Var(name, ..) = assign.as_ref() {
+                    variable.insert_local(name.to_string(), None);
                 }
-
-                process_stmt(&func.stmt_list, variable, draw, function, file)?;
             }
             _ => continue,
         }
     }
+    Ok(Flow::Continue)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn process_call(
+    name: &str,
+    args: &[Expr],
+    end: usize,
+    len: usize,
+    line: usize,
+    variable: &mut Variable,
+    draw: &mut Draw,
+    function: &Function,
+    file: &[String],
+) -> Result<Flow, GenerationError<'static>> {
+    let func = match function.get(name) {
+        Some(func) => func,
+        None => {
+            return Err(match_err(
+                file[line].to_string(),
+                line,
+                "UnDefinedFunction".to_string(),
+                end,
+                len,
+            ))
+        }
+    };
+
+    if args.len() > func.args.len() {
+        return Err(match_err(
+            file[line].to_string(),
+            line,
+            "TooManyArguments".to_string(),
+            end,
+            len,
+        ));
+    } else if args.len() < func.args.len() {
+        return Err(match_err(
+            file[line].to_string(),
+            line,
+            "MissingArguments".to_string(),
+            end,
+            len,
+        ));
+    }
+
+    let mut values = Vec::new();
+    for value in args.iter() {
+        values.push(process_expr(
+            value,
+            variable,
+            line,
+            &file[line],
+            draw,
+            function,
+            file,
+        )?);
+    }
+
+    check_limit(variable, line, file)?;
+
+    if variable.is_max_depth() {
+        return Err(match_err(
+            file[line].to_string(),
+            line,
+            "RecursionLimitExceeded".to_string(),
+            end,
+            len,
+        ));
+    }
+
+    variable.push_scope();
+    for (arg, value) in func.args.iter().zip(values) {
+        if let Assign::Var(name, ..) = &arg {
+            match value {
+                Value::F(num) => variable.insert_local(name.to_string(), Some(Type::F(num))),
+                Value::B(bool) => variable.insert_local(name.to_string(), Some(Type::B(bool))),
+            }
+        }
+    }
+
+    let flow = process_stmt(&func.stmt_list, variable, draw, function, file)
+        .map_err(|e| e.trace(name, line));
+    variable.pop_scope();
+    flow
+}
+
+#[allow(clippy::too_many_arguments)]
+fn process_integer(
+    expr: &Expr,
+    line: usize,
+    max: f32,
+    err: &str,
+    variable: &mut Variable,
+    draw: &mut Draw,
+    function: &Function,
+    file: &[String],
+) -> Result<f32, GenerationError<'static>> {
+    let (end, len) = get_end_len(expr);
+    match process_expr(expr, variable, line, &file[line], draw, function, file)? {
+        Value::B(_) => Err(match_err(
+            file[line].to_string(),
+            line,
+            "UnexpectedBooleanType".to_string(),
+            end,
+            len,
+        )),
+        Value::F(num) if !num.is_finite() || num.fract() != 0.0 => Err(match_err(
+            file[line].to_string(),
+            line,
+            "NonIntegerValueError".to_string(),
+            end,
+            len,
+        )),
+        Value::F(num) if !(0.0..=max).contains(&num) => Err(match_err(
+            file[line].to_string(),
+            line,
+            err.to_string(),
+            end,
+            len,
+        )),
+        Value::F(num) => Ok(num),
+    }
+}
+
+fn process_finite(
+    expr: &Expr,
+    line: usize,
+    err: &str,
+    variable: &mut Variable,
+    draw: &mut Draw,
+    function: &Function,
+    file: &[String],
+) -> Result<f32, GenerationError<'static>> {
+    let (end, len) = get_end_len(expr);
+    match process_expr(expr, variable, line, &file[line], draw, function, file)? {
+        Value::B(_) => Err(match_err(
+            file[line].to_string(),
+            line,
+            "UnexpectedNumberType".to_string(),
+            end,
+            len,
+        )),
+        Value::F(num) if !num.is_finite() => Err(match_err(
+            file[line].to_string(),
+            line,
+            err.to_string(),
+            end,
+            len,
+        )),
+        Value::F(num) => Ok(num),
+    }
+}
+
+fn process_positive(
+    expr: &Expr,
+    line: usize,
+    err: &str,
+    variable: &mut Variable,
+    draw: &mut Draw,
+    function: &Function,
+    file: &[String],
+) -> Result<f32, GenerationError<'static>> {
+    let (end, len) = get_end_len(expr);
+    match process_expr(expr, variable, line, &file[line], draw, function, file)? {
+        Value::B(_) => Err(match_err(
+            file[line].to_string(),
+            line,
+            "UnexpectedNumberType".to_string(),
+            end,
+            len,
+        )),
+        Value::F(num) if !num.is_finite() || num <= 0.0 => Err(match_err(
+            file[line].to_string(),
+            line,
+            err.to_string(),
+            end,
+            len,
+        )),
+        Value::F(num) => Ok(num),
+    }
+}
+
+fn check_move(
+    moved: Result<(), &str>,
+    line: usize,
+    file: &[String],
+) -> Result<(), GenerationError<'static>> {
+    if let Err(err) = moved {
+        return Err(match_err(
+            file[line].to_string(),
+            line,
+            err.to_string(),
+            0,
+            file[line].trim_start().len(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_limit(
+    variable: &Variable,
+    line: usize,
+    file: &[String],
+) -> Result<(), GenerationError<'static>> {
+    if variable.is_exhausted() {
+        return Err(match_err(
+            file[line].to_string(),
+            line,
+            "StepLimitExceeded".to_string(),
+            0,
+            file[line].trim_start().len(),
+        ));
+    }
     Ok(())
 }
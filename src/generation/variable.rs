@@ -1,4 +1,40 @@
+use crate::generation::random::Random;
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_MAX_DEPTH: usize = 1000;
+/// Enough for any drawing that finishes, and small enough that a loop which never ends stops
+/// within seconds.
+pub const DEFAULT_MAX_STEPS: u64 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Limit {
+    pub max_depth: usize,
+    /// How many bytes of stack procedure calls may use, counting the blocks and expressions
+    /// they are nested in. Calls past it fail like calls past `max_depth`, instead of
+    /// overflowing the stack of the thread running the program.
+    pub max_stack: Option<usize>,
+    pub max_steps: Option<u64>,
+    pub timeout: Option<Duration>,
+}
+
+impl Default for Limit {
+    fn default() -> Self {
+        Limit {
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_stack: None,
+            max_steps: Some(DEFAULT_MAX_STEPS),
+            timeout: None,
+        }
+    }
+}
+
+/// The address of a local in a new stack frame, to measure how deep the interpreter is.
+#[inline(never)]
+fn stack_address() -> usize {
+    let marker = 0u8;
+    std::hint::black_box(&marker) as *const u8 as usize
+}
 
 #[derive(Debug, PartialEq)]
 pub enum Type {
@@ -8,31 +44,118 @@ pub enum Type {
 
 #[derive(Debug, PartialEq)]
 pub struct Variable {
-    map: HashMap<String, Option<Type>>,
+    scope: Vec<HashMap<String, Option<Type>>>,
+    /// The counters of the `REPEAT` loops running in each frame. A procedure only sees the
+    /// loops in its own body, the same as a transpiled procedure.
+    repcount: Vec<Vec<f32>>,
+    limit: Limit,
+    /// Where the stack was when the program started.
+    stack: usize,
+    steps: u64,
+    deadline: Option<Instant>,
+    random: Random,
 }
 
 impl Variable {
-    pub fn new() -> Self {
+    pub fn new(limit: Limit, seed: u64) -> Self {
         Variable {
-            map: HashMap::new(),
+            scope: vec![HashMap::new()],
+            repcount: vec![Vec::new()],
+            limit,
+            stack: stack_address(),
+            steps: 0,
+            deadline: limit.timeout.map(|timeout| Instant::now() + timeout),
+            random: Random::new(seed),
         }
     }
 
+    /// The frame that holds `name`, searching from the running procedure down to the main
+    /// program. Variables have no scope, so a name no frame holds is made in the main
+    /// program's frame, and only arguments and `LOCAL` names belong to a single call.
+    fn frame(&mut self, name: &str) -> &mut HashMap<String, Option<Type>> {
+        let idx = self
+            .scope
+            .iter()
+            .rposition(|map| map.contains_key(name))
+            .unwrap_or(0);
+        &mut self.scope[idx]
+    }
+
     pub fn insert_num(&mut self, name: String, var: Option<f32>) {
-        match var {
-            Some(num) => self.map.insert(name, Some(Type::F(num))),
-            None => self.map.insert(name, None),
-        };
+        self.frame(&name).insert(name, var.map(Type::F));
     }
 
     pub fn insert_bool(&mut self, name: String, var: Option<bool>) {
-        match var {
-            Some(bool) => self.map.insert(name, Some(Type::B(bool))),
-            None => self.map.insert(name, None),
-        };
+        self.frame(&name).insert(name, var.map(Type::B));
+    }
+
+    pub fn insert_local(&mut self, name: String, var: Option<Type>) {
+        self.scope
+            .last_mut()
+            .expect("Expected a frame in scope")
+            .insert(name, var);
     }
 
     pub fn get(&self, name: &str) -> Option<&Option<Type>> {
-        self.map.get(name)
+        self.scope.iter().rev().find_map(|map| map.get(name))
+    }
+
+    pub fn push_scope(&mut self) {
+        self.scope.push(HashMap::new());
+        self.repcount.push(Vec::new());
+    }
+
+    pub fn pop_scope(&mut self) {
+        if self.scope.len() > 1 {
+            self.scope.pop();
+            self.repcount.pop();
+        }
+    }
+
+    pub fn is_max_depth(&self) -> bool {
+        self.scope.len() > self.limit.max_depth
+            || self
+                .limit
+                .max_stack
+                .is_some_and(|max| self.stack.abs_diff(stack_address()) > max)
+    }
+
+    pub fn step(&mut self) {
+        self.steps += 1;
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.limit.max_steps.is_some_and(|max| self.steps > max)
+            || self
+                .deadline
+                .is_some_and(|deadline| Instant::now() > deadline)
+    }
+
+    pub fn random(&mut self) -> &mut Random {
+        &mut self.random
+    }
+
+    fn loops(&mut self) -> &mut Vec<f32> {
+        self.repcount
+            .last_mut()
+            .expect("Expected a frame in repcount")
+    }
+
+    pub fn push_repcount(&mut self) {
+        self.loops().push(0.0);
+    }
+
+    pub fn next_repcount(&mut self) {
+        if let Some(count) = self.loops().last_mut() {
+            *count += 1.0;
+        }
+    }
+
+    pub fn pop_repcount(&mut self) {
+        self.loops().pop();
+    }
+
+    pub fn repcount(&self) -> Option<f32> {
+        self.repcount.last().and_then(|loops| loops.last()).copied()
     }
 }
@@ -0,0 +1,261 @@
+use std::path::Path;
+use unsvg::Color;
+
+use crate::generation::display::{arc_curves, DisplayList, Shape};
+
+#[derive(Clone, Copy, PartialEq)]
+enum Format {
+    Pdf,
+    Eps,
+}
+
+fn number(x: f32) -> String {
+    let x = format!("{x:.3}");
+    let x = x.trim_end_matches('0').trim_end_matches('.');
+    match x {
+        "-0" => "0".to_string(),
+        _ => x.to_string(),
+    }
+}
+
+fn rgb(color: Color) -> String {
+    [color.red, color.green, color.blue]
+        .iter()
+        .map(|&c| number(c as f32 / 255.0))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Escapes a label for a PDF or PostScript string, which both use the same syntax.
+fn string(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '\\' | '(' | ')' => format!("\\{c}"),
+            ' '..='~' => c.to_string(),
+            _ => "?".to_string(),
+        })
+        .collect()
+}
+
+/// Writes the drawing as page content, flipping it so the origin is in the bottom left.
+struct Page {
+    format: Format,
+    view: (f32, f32, f32, f32),
+    content: String,
+}
+
+impl Page {
+    fn point(&self, (x, y): (f32, f32)) -> String {
+        let (left, top, _, height) = self.view;
+        format!("{} {}", number(x - left), number(top + height - y))
+    }
+
+    fn op(&mut self, operands: String, pdf: &str, eps: &str) {
+        let operator = match self.format {
+            Format::Pdf => pdf,
+            Format::Eps => eps,
+        };
+        match operands.is_empty() {
+            true => self.content.push_str(&format!("{operator}\n")),
+            false => self.content.push_str(&format!("{operands} {operator}\n")),
+        }
+    }
+
+    fn move_to(&mut self, point: (f32, f32)) {
+        if self.format == Format::Eps {
+            self.content.push_str("newpath\n");
+        }
+        self.op(self.point(point), "m", "moveto");
+    }
+
+    fn line_to(&mut self, point: (f32, f32)) {
+        self.op(self.point(point), "l", "lineto");
+    }
+
+    /// Adds an arc as cubic Béziers of at most 90 degrees each.
+    fn arc_to(&mut self, center: (f32, f32), radius: f32, from: f32, angle: f32) {
+        for points in arc_curves(center, radius, from, angle) {
+            let operands = points.map(|point| self.point(point)).join(" ");
+            self.op(operands, "c", "curveto");
+        }
+    }
+
+    fn stroke(&mut self, color: Color, width: f32) {
+        self.op(rgb(color), "RG", "setrgbcolor");
+        self.op(number(width), "w", "setlinewidth");
+        self.op(String::new(), "S", "stroke");
+    }
+
+    fn fill(&mut self, color: Color) {
+        self.op(String::new(), "h", "closepath");
+        self.op(rgb(color), "rg", "setrgbcolor");
+        self.op(String::new(), "f", "fill");
+    }
+
+    fn label(
+        &mut self,
+        text: &str,
+        position: (f32, f32),
+        direction: f32,
+        height: f32,
+        color: Color,
+    ) {
+        let (sin, cos) = (90.0 - direction).to_radians().sin_cos();
+        let text = string(text);
+        self.op(rgb(color), "rg", "setrgbcolor");
+        match self.format {
+            Format::Pdf => {
+                self.content.push_str(&format!(
+                    "BT /F1 {} Tf {} {} {} {} {} Tm ({text}) Tj ET\n",
+                    number(height),
+                    number(cos),
+                    number(sin),
+                    number(-sin),
+                    number(cos),
+                    self.point(position),
+                ));
+            }
+            Format::Eps => {
+                self.content.push_str(&format!(
+                    "gsave {} translate {} rotate /Helvetica findfont {} scalefont setfont \
+                     0 0 moveto ({text}) show grestore\n",
+                    self.point(position),
+                    number(90.0 - direction),
+                    number(height),
+                ));
+            }
+        }
+    }
+
+    fn draw(&mut self, shape: &Shape) {
+        match shape {
+            Shape::Line {
+                start,
+                end,
+                color,
+                width,
+            } => {
+                self.move_to(*start);
+                self.line_to(*end);
+                self.stroke(*color, *width);
+            }
+            Shape::Arc {
+                start,
+                center,
+                radius,
+                angle,
+                color,
+                width,
+                ..
+            } => {
+                let from = (start.1 - center.1).atan2(start.0 - center.0).to_degrees();
+                self.move_to(*start);
+                self.arc_to(*center, *radius, from, *angle);
+                self.stroke(*color, *width);
+            }
+            Shape::Circle {
+                center,
+                radius,
+                color,
+                width,
+            } => {
+                self.move_to((center.0 + radius, center.1));
+                self.arc_to(*center, *radius, 0.0, 360.0);
+                self.op(String::new(), "h", "closepath");
+                self.stroke(*color, *width);
+            }
+            Shape::Fill { points, color } => {
+                self.move_to(points[0]);
+                for &point in &points[1..] {
+                    self.line_to(point);
+                }
+                self.fill(*color);
+            }
+            Shape::Label {
+                text,
+                position,
+                direction,
+                height,
+                color,
+            } => self.label(text, *position, *direction, *height, *color),
+        }
+    }
+
+    fn render(format: Format, display: &DisplayList) -> String {
+        let mut page = Page {
+            format,
+            view: display.view,
+            content: String::new(),
+        };
+        let (left, top, width, height) = display.view;
+        page.move_to((left, top));
+        for corner in [
+            (left + width, top),
+            (left + width, top + height),
+            (left, top + height),
+        ] {
+            page.line_to(corner);
+        }
+        page.fill(display.background);
+        for shape in &display.shapes {
+            page.draw(shape);
+        }
+        page.content
+    }
+}
+
+pub fn save_pdf<P: AsRef<Path>>(display: &DisplayList, path: P) -> Result<(), String> {
+    let (_, _, width, height) = display.view;
+    let content = Page::render(Format::Pdf, display);
+    let objects = [
+        "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+        "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+        format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {} {}] /Contents 4 0 R \
+             /Resources << /Font << /F1 5 0 R >> >> >>",
+            number(width),
+            number(height)
+        ),
+        format!(
+            "<< /Length {} >>\nstream\n{content}endstream",
+            content.len()
+        ),
+        "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica >>".to_string(),
+    ];
+
+    let mut pdf = "%PDF-1.4\n".to_string();
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(pdf.len());
+        pdf.push_str(&format!("{} 0 obj\n{object}\nendobj\n", index + 1));
+    }
+    let xref = pdf.len();
+    pdf.push_str(&format!(
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    ));
+    for offset in offsets {
+        pdf.push_str(&format!("{offset:010} 00000 n \n"));
+    }
+    pdf.push_str(&format!(
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    ));
+
+    std::fs::write(path, pdf).map_err(|e| e.to_string())
+}
+
+pub fn save_eps<P: AsRef<Path>>(display: &DisplayList, path: P) -> Result<(), String> {
+    let (_, _, width, height) = display.view;
+    let eps = format!(
+        "%!PS-Adobe-3.0 EPSF-3.0\n%%BoundingBox: 0 0 {} {}\n%%HiResBoundingBox: 0 0 {} {}\n\
+         %%EndComments\n{}showpage\n%%EOF\n",
+        width.ceil(),
+        height.ceil(),
+        number(width),
+        number(height),
+        Page::render(Format::Eps, display),
+    );
+
+    std::fs::write(path, eps).map_err(|e| e.to_string())
+}
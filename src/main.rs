@@ -1,70 +1,431 @@
-use clap::Parser;
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use miette::{miette, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 mod ast;
 mod generation;
 mod transpiler;
 
-use ast::parse_ast;
-use generation::code_generation;
-use transpiler::transpiler_rust;
+use ast::{format_source, parse_ast};
+use generation::{
+    code_generation, preview, save_eps, save_pdf, Animation, Boundary, Canvas, Image, Limit,
+    Plotter, DEFAULT_MAX_DEPTH, DEFAULT_MAX_STEPS,
+};
+use transpiler::{check_rust, transpiler_rust};
 
-/// A simple program to parse four arguments using clap.
+/// A Logo interpreter that draws programs as images or transpiles them to Rust.
 #[derive(Parser)]
-struct Args {
-    /// Path to a file
-    file_path: std::path::PathBuf,
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
 
-    /// Path to an svg or png image
-    image_path: std::path::PathBuf,
+#[derive(Subcommand)]
+enum Command {
+    /// Run a program and save what it draws
+    Render(RenderArgs),
+    /// Parse and type-check a program without running it
+    Check {
+        /// Path to a file
+        file_path: PathBuf,
 
-    /// Height
-    height: u32,
+        /// Also check the program can be transpiled to this language
+        #[arg(long, value_enum)]
+        target: Option<Target>,
+    },
+    /// Translate a program into a Cargo project
+    Transpile(TranspileArgs),
+    /// Print a program with consistent spacing and indentation
+    Fmt {
+        /// Path to a file
+        file_path: PathBuf,
+
+        /// Overwrite the file instead of printing it
+        #[arg(long)]
+        write: bool,
+    },
+    /// Print the syntax tree of a program
+    Ast {
+        /// Path to a file
+        file_path: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Format {
+    Svg,
+    Png,
+    Gif,
+    Pdf,
+    Eps,
+    Hpgl,
+    Gcode,
+}
 
-    /// Width
+impl Format {
+    fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        Self::from_str(extension, true).ok()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Target {
+    Rust,
+}
+
+#[derive(Args)]
+struct CanvasArgs {
+    /// Width of the canvas
+    #[arg(long)]
     width: u32,
+
+    /// Height of the canvas
+    #[arg(long)]
+    height: u32,
+
+    /// Seed for `RANDOM`, `RANDOMFLOAT` and `PICK`
+    #[arg(long)]
+    seed: Option<u64>,
+
+    /// Starting position of the turtle, measured from the top left corner
+    #[arg(long, num_args = 2, value_names = ["X", "Y"], allow_negative_numbers = true)]
+    origin: Option<Vec<f32>>,
+
+    /// Starting heading of the turtle in degrees
+    #[arg(long, default_value_t = 0.0, allow_negative_numbers = true)]
+    heading: f32,
+
+    /// What happens when the turtle reaches the edge of the canvas
+    #[arg(long, value_enum, default_value_t = Boundary::Window)]
+    boundary: Boundary,
 }
 
-fn main() -> Result<()> {
-    let args: Args = Args::parse();
+#[derive(Args)]
+struct TranspileArgs {
+    /// Path to a file
+    file_path: PathBuf,
 
-    // Access the parsed arguments
-    let file_path = args.file_path;
-    let image_path = args.image_path;
-    let height = args.height;
-    let width = args.width;
+    /// Name of the project to create in the current directory
+    output: PathBuf,
 
-    let file = match std::fs::read_to_string(file_path) {
-        Ok(file) => file.lines().map(|x| x.to_string()).collect::<Vec<String>>(),
-        Err(e) => return Err(miette!(e)),
-    };
+    /// Language to transpile to
+    #[arg(long, value_enum, default_value_t = Target::Rust)]
+    target: Target,
 
-    let ast = parse_ast(&file)?;
+    #[command(flatten)]
+    canvas: CanvasArgs,
+
+    /// Size the image to fit the drawing, leaving a margin around it
+    #[arg(long, value_name = "MARGIN", num_args = 0..=1, default_missing_value = "10")]
+    auto_size: Option<f32>,
+}
+
+#[derive(Args)]
+struct RenderArgs {
+    /// Path to a file
+    file_path: PathBuf,
+
+    /// Path to an svg, png, gif, pdf or eps image, hpgl or gcode plotter output, or `-` to only
+    /// preview the drawing in the terminal
+    image_path: PathBuf,
+
+    /// Output format, instead of guessing it from the extension
+    #[arg(long, value_enum)]
+    format: Option<Format>,
+
+    #[command(flatten)]
+    canvas: CanvasArgs,
+
+    /// Maximum depth of nested function calls
+    #[arg(long, default_value_t = DEFAULT_MAX_DEPTH)]
+    max_depth: usize,
+
+    /// Maximum number of statements to execute
+    #[arg(long, default_value_t = DEFAULT_MAX_STEPS)]
+    max_steps: u64,
+
+    /// Maximum running time in seconds
+    #[arg(long)]
+    timeout: Option<f64>,
+
+    /// Size the image to fit the drawing, leaving a margin around it
+    #[arg(long, value_name = "MARGIN", num_args = 0..=1, default_missing_value = "10")]
+    auto_size: Option<f32>,
+
+    /// Animate how the drawing is built in an svg; gif images are always animated
+    #[arg(long)]
+    animate: bool,
+
+    /// Frames per second of the animation
+    #[arg(long, default_value_t = 10.0)]
+    fps: f32,
+
+    /// Number of drawing steps added in each frame of the animation
+    #[arg(long, default_value_t = 1)]
+    segments_per_frame: usize,
+
+    /// Plotter feed rate in millimetres per minute
+    #[arg(long, default_value_t = 1000.0)]
+    feed_rate: f32,
+
+    /// Plotter millimetres per pixel
+    #[arg(long, default_value_t = 1.0)]
+    scale: f32,
 
-    match image_path.extension().and_then(|s| s.to_str()) {
-        Some("svg") => {
-            let image = code_generation(ast, &file, width, height)?;
+    /// G-code that lifts the pen
+    #[arg(long, default_value = "G0 Z5", allow_hyphen_values = true)]
+    pen_up: String,
 
-            let res = image.save_svg(&image_path);
-            if let Err(e) = res {
-                return Err(miette!("Error saving svg: {e}"));
+    /// G-code that lowers the pen
+    #[arg(long, default_value = "G1 Z0", allow_hyphen_values = true)]
+    pen_down: String,
+
+    /// Reorder plotter paths to reduce travel with the pen up
+    #[arg(long)]
+    optimize_paths: bool,
+
+    /// Also print the drawing to the terminal as braille characters
+    #[arg(long)]
+    preview: bool,
+
+    /// Width of the terminal preview in characters
+    #[arg(long, default_value_t = 80)]
+    columns: u32,
+}
+
+/// Stack for everything but procedure calls, including the blocks they are nested in.
+const STACK_SIZE: usize = 8 * 1024 * 1024;
+/// Stack for each procedure call allowed by `--max-depth`, enough for a call nested in a few
+/// blocks or inside an expression in a debug build.
+const STACK_PER_CALL: usize = 256 * 1024;
+/// The most stack the interpreter asks for, however high `--max-depth` is. Deeper calls
+/// fail with a recursion limit error once it is used up.
+const MAX_STACK_SIZE: usize = 1024 * 1024 * 1024;
+
+/// The stack size of the thread running a command that allows `max_depth` nested calls.
+fn stack_size(max_depth: usize) -> usize {
+    STACK_SIZE
+        .saturating_add(max_depth.saturating_mul(STACK_PER_CALL))
+        .min(MAX_STACK_SIZE)
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let max_depth = match &cli.command {
+        Command::Render(args) => args.max_depth,
+        _ => DEFAULT_MAX_DEPTH,
+    };
+    let stack_size = stack_size(max_depth);
+
+    match std::thread::Builder::new()
+        .stack_size(stack_size)
+        .spawn(move || run(cli.command))
+    {
+        Ok(handle) => handle
+            .join()
+            .unwrap_or_else(|_| Err(miette!("Interpreter thread panicked"))),
+        Err(e) => Err(miette!(
+            "Could not reserve {} MiB of stack for the interpreter: {e}",
+            stack_size / (1024 * 1024)
+        )),
+    }
+}
+
+fn run(command: Command) -> Result<()> {
+    match command {
+        Command::Render(args) => render(args),
+        Command::Check { file_path, target } => {
+            let file = read_file(&file_path)?;
+            let ast = parse_ast(&file)?;
+            if target == Some(Target::Rust) {
+                check_rust(&ast, &file)?;
             }
+            println!("{}: no errors found", file_path.display());
+            Ok(())
         }
-        Some("png") => {
-            let image = code_generation(ast, &file, width, height)?;
-
-            let res = image.save_png(&image_path);
-            if let Err(e) = res {
-                return Err(miette!("Error saving png: {e}"));
+        Command::Transpile(args) => transpile(args),
+        Command::Fmt { file_path, write } => {
+            let file = read_file(&file_path)?;
+            parse_ast(&file)?;
+            let formatted = format_source(&file);
+            match write {
+                true => std::fs::write(&file_path, formatted).map_err(|e| miette!(e)),
+                false => {
+                    print!("{formatted}");
+                    Ok(())
+                }
             }
         }
-        None => {
-            if image_path.is_dir() || image_path.display().to_string().contains('/') {
+        Command::Ast { file_path } => {
+            let file = read_file(&file_path)?;
+            let ast = parse_ast(&file)?;
+            let functions = ast.get_all().iter().collect::<BTreeMap<_, _>>();
+            println!("{functions:#?}");
+            Ok(())
+        }
+    }
+}
+
+fn read_file(file_path: &Path) -> Result<Vec<String>> {
+    match std::fs::read_to_string(file_path) {
+        Ok(file) => Ok(file.lines().map(|x| x.to_string()).collect::<Vec<String>>()),
+        Err(e) => Err(miette!(e)),
+    }
+}
+
+impl CanvasArgs {
+    fn canvas(&self) -> Result<Canvas> {
+        let mut canvas = Canvas::new(self.width, self.height);
+        if let Some(origin) = &self.origin {
+            canvas.origin = (origin[0], origin[1]);
+        }
+        canvas.heading = self.heading;
+        canvas.boundary = self.boundary;
+        if !canvas.origin.0.is_finite() || !canvas.origin.1.is_finite() {
+            return Err(miette!(
+                "Invalid origin: coordinates must be finite numbers"
+            ));
+        }
+        if !canvas.heading.is_finite() {
+            return Err(miette!("Invalid heading: must be a finite number"));
+        }
+        Ok(canvas)
+    }
+
+    fn seed(&self) -> u64 {
+        self.seed.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|time| time.as_nanos() as u64)
+                .unwrap_or_default()
+        })
+    }
+}
+
+fn auto_size(margin: Option<f32>) -> Result<Option<f32>> {
+    match margin {
+        Some(margin) if !margin.is_finite() || margin < 0.0 => {
+            Err(miette!("Invalid margin: must be a non-negative number"))
+        }
+        margin => Ok(margin),
+    }
+}
+
+fn transpile(args: TranspileArgs) -> Result<()> {
+    let mut canvas = args.canvas.canvas()?;
+    canvas.auto_size = auto_size(args.auto_size)?;
+    let file = read_file(&args.file_path)?;
+    let ast = parse_ast(&file)?;
+
+    match args.target {
+        Target::Rust => {
+            let output = args.output;
+            if output.is_dir() || output.display().to_string().contains('/') {
                 return Err(miette!("Rust transpiler does not support directories"));
             }
-            transpiler_rust(&image_path, ast, &file, width, height)?
+            transpiler_rust(&output, ast, &file, &canvas, args.canvas.seed())
+        }
+    }
+}
+
+fn render(args: RenderArgs) -> Result<()> {
+    let image_path = args.image_path;
+    let limit = Limit {
+        max_depth: args.max_depth,
+        max_stack: Some(stack_size(args.max_depth) - STACK_SIZE),
+        max_steps: Some(args.max_steps),
+        timeout: match args.timeout.map(std::time::Duration::try_from_secs_f64) {
+            Some(Ok(timeout)) => Some(timeout),
+            Some(Err(e)) => return Err(miette!("Invalid timeout: {e}")),
+            None => None,
+        },
+    };
+
+    let preview_only = image_path == Path::new("-");
+    if preview_only && args.format.is_some() {
+        return Err(miette!(
+            "--format needs an image path; `-` only previews the drawing in the terminal"
+        ));
+    }
+    let format = match args.format.or_else(|| Format::from_path(&image_path)) {
+        Some(format) => Some(format),
+        None if preview_only => None,
+        None => {
+            return Err(miette!(
+                "File extension not supported; use --format to choose the output format"
+            ))
+        }
+    };
+
+    let mut canvas = args.canvas.canvas()?;
+    canvas.auto_size = auto_size(args.auto_size)?;
+    canvas.animate = args.animate || format == Some(Format::Gif);
+    if !args.fps.is_finite() || args.fps <= 0.0 {
+        return Err(miette!("Invalid fps: must be a positive number"));
+    }
+    if args.segments_per_frame == 0 {
+        return Err(miette!("Invalid segments per frame: must be at least 1"));
+    }
+    if args.columns == 0 {
+        return Err(miette!("Invalid columns: must be at least 1"));
+    }
+    if args.animate && !matches!(format, Some(Format::Svg) | Some(Format::Gif)) {
+        return Err(miette!("Only svg and gif images can be animated"));
+    }
+    let animation = Animation {
+        fps: args.fps,
+        segments_per_frame: args.segments_per_frame,
+    };
+    if !args.feed_rate.is_finite() || args.feed_rate <= 0.0 {
+        return Err(miette!("Invalid feed rate: must be a positive number"));
+    }
+    if !args.scale.is_finite() || args.scale <= 0.0 {
+        return Err(miette!("Invalid scale: must be a positive number"));
+    }
+    let plotter = Plotter {
+        feed_rate: args.feed_rate,
+        scale: args.scale,
+        pen_up: args.pen_up,
+        pen_down: args.pen_down,
+        optimize: args.optimize_paths,
+    };
+
+    let file = read_file(&args.file_path)?;
+    let ast = parse_ast(&file)?;
+    let display = code_generation(ast, &file, &canvas, limit, args.canvas.seed())?;
+
+    let res = match format {
+        Some(Format::Svg) => match canvas.animate {
+            true => animation.save_svg(&display, &image_path),
+            false => Image::render(&display).and_then(|image| image.save_svg(&image_path)),
+        },
+        Some(Format::Png) => {
+            Image::render(&display).and_then(|mut image| image.save_png(&image_path))
+        }
+        Some(Format::Gif) => animation.save_gif(&display, &image_path),
+        Some(Format::Pdf) => save_pdf(&display, &image_path),
+        Some(Format::Eps) => save_eps(&display, &image_path),
+        Some(Format::Hpgl) => plotter.save_hpgl(&display, &image_path),
+        Some(Format::Gcode) => plotter.save_gcode(&display, &image_path),
+        // Only previewing, so there is nothing to save.
+        None => Ok(()),
+    };
+    if let (Err(e), Some(format)) = (res, format) {
+        let name = format
+            .to_possible_value()
+            .map(|value| value.get_name().to_string());
+        return Err(miette!("Error saving {}: {e}", name.unwrap_or_default()));
+    }
+
+    if args.preview || preview_only {
+        let color = std::env::var_os("NO_COLOR").is_none();
+        match preview(&display, args.columns, color) {
+            Ok(text) => print!("{text}"),
+            Err(e) => return Err(miette!("Error previewing image: {e}")),
         }
-        _ => return Err(miette!("File extension not supported")),
     }
 
     Ok(())
@@ -69,6 +69,14 @@ pub enum TranspilerError<'a> {
         help: &'a str,
         error: String,
     },
+    UnDefinedRepCount {
+        #[source_code]
+        src: String,
+        #[label("`REPCOUNT` is only available inside a `REPEAT` block.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
     UnDefinedFunction {
         #[source_code]
         src: String,
@@ -77,6 +85,14 @@ pub enum TranspilerError<'a> {
         help: &'a str,
         error: String,
     },
+    MissingOutput {
+        #[source_code]
+        src: String,
+        #[label("Function has no `OUTPUT` statement.")]
+        bad_bit: SourceSpan,
+        help: &'a str,
+        error: String,
+    },
     TooManyArguments {
         #[source_code]
         src: String,
@@ -118,7 +134,9 @@ pub fn match_err<'a>(
             src,
             bad_bit: (start, len).into(),
             help: "Replace with an expression that returns a number, choosing from `FLOAT`, \
-            `VAR`, `ADD`, `SUB`, `MUL`, `DIV`, `XCOR`, `YCOR`, `HEADING`, or `COLOR`.",
+            `VAR`, `ADD`, `SUB`, `MUL`, `DIV`, `XCOR`, `YCOR`, `HEADING`, `COLOR`, `FILLCOLOR`, \
+            `PENSIZE`, `REPCOUNT`, \
+            or a math function such as `SIN` or `SQRT`.",
             error: format!(
                 "Unexpected `boolean` type in expression (Ln {line}, Col {})",
                 start + 1
@@ -164,12 +182,28 @@ pub fn match_err<'a>(
                 start + 1
             ),
         },
+        "UnDefinedRepCount" => TranspilerError::UnDefinedRepCount {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Move `REPCOUNT` into the body of a `REPEAT` statement in the same procedure, \
+                or pass the count to the procedure as an argument.",
+            error: format!(
+                "Undefined repeat count error (Ln {line}, Col {})",
+                start + 1
+            ),
+        },
         "UnDefinedFunction" => TranspilerError::UnDefinedFunction {
             src,
             bad_bit: (start, len).into(),
             help: "Define the `TO` function before using it.",
             error: format!("Undefined function error (Ln {line}, Col {})", start + 1),
         },
+        "MissingOutput" => TranspilerError::MissingOutput {
+            src,
+            bad_bit: (start, len).into(),
+            help: "Add an `OUTPUT` statement to the function before using it in an expression.",
+            error: format!("Missing output error (Ln {line}, Col {})", start + 1),
+        },
         "TooManyArguments" => TranspilerError::TooManyArguments {
             src,
             bad_bit: (start, len).into(),
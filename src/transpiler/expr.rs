@@ -1,10 +1,25 @@
-use crate::ast::structs::Expr;
+use crate::ast::structs::{Expr, Function};
 use crate::transpiler::{
     err::{match_err, TranspilerError},
     file::DrawMethod,
+    func::{call, output_type, STOP_ERROR},
 };
 use std::collections::HashMap;
 
+/// The `REPEAT` loop counter. User variables are always prefixed by `var_name`, so no
+/// Logo variable can be emitted under this name.
+pub const REPCOUNT: &str = "repcount";
+
+pub fn var_name(name: &str) -> String {
+    format!("var_{}", name)
+}
+
+/// Whether `value` calls a procedure. A call borrows `draw` mutably, so it is bound to a
+/// variable first when it is the argument of another call on `draw`.
+pub fn has_call(value: &str) -> bool {
+    value.contains("draw.call(")
+}
+
 pub enum Value {
     F(String),
     B(String),
@@ -16,6 +31,7 @@ pub fn transpiler_expr<'a>(
     sentence: &str,
     variable: &HashMap<String, bool>,
     method: &mut DrawMethod,
+    ast: &Function,
 ) -> Result<Value, TranspilerError<'a>> {
     match expr {
         Expr::Boolean(bool, ..) => Ok(Value::B(bool.to_string())),
@@ -27,9 +43,15 @@ pub fn transpiler_expr<'a>(
                 Ok(Value::F(format!("{}.0", temp)))
             }
         }
-        Expr::Var(var, end, len) => match variable.get(var) {
-            Some(true) => Ok(Value::B(var.to_string())),
-            Some(false) => Ok(Value::F(var.to_string())),
+        Expr::Var(var, end, len) => match variable.get(&var_name(var)) {
+            Some(true) => {
+                method.insert("boolean".to_string());
+                Ok(Value::B(format!("draw.boolean({:?})?", var)))
+            }
+            Some(false) => {
+                method.insert("num".to_string());
+                Ok(Value::F(format!("draw.num({:?})?", var)))
+            }
             None => Err(match_err(
                 sentence.to_string(),
                 line,
@@ -40,8 +62,8 @@ pub fn transpiler_expr<'a>(
         },
         Expr::Add(expr1, expr2, ..) => {
             match (
-                transpiler_expr(expr1, line, sentence, variable, method)?,
-                transpiler_expr(expr2, line, sentence, variable, method)?,
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
             ) {
                 (Value::F(left), Value::F(right)) => Ok(Value::F(format!(
                     "{} + {}",
@@ -72,8 +94,8 @@ pub fn transpiler_expr<'a>(
         }
         Expr::Sub(expr1, expr2, ..) => {
             match (
-                transpiler_expr(expr1, line, sentence, variable, method)?,
-                transpiler_expr(expr2, line, sentence, variable, method)?,
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
             ) {
                 (Value::F(left), Value::F(right)) => Ok(Value::F(format!(
                     "{} - {}",
@@ -104,8 +126,8 @@ pub fn transpiler_expr<'a>(
         }
         Expr::Mul(expr1, expr2, ..) => {
             match (
-                transpiler_expr(expr1, line, sentence, variable, method)?,
-                transpiler_expr(expr2, line, sentence, variable, method)?,
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
             ) {
                 (Value::F(left), Value::F(right)) => Ok(Value::F(format!(
                     "{} * {}",
@@ -136,8 +158,8 @@ pub fn transpiler_expr<'a>(
         }
         Expr::Div(expr1, expr2, ..) => {
             match (
-                transpiler_expr(expr1, line, sentence, variable, method)?,
-                transpiler_expr(expr2, line, sentence, variable, method)?,
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
             ) {
                 (Value::F(left), Value::F(right)) => Ok(Value::F(format!(
                     "{} / {}",
@@ -168,8 +190,8 @@ pub fn transpiler_expr<'a>(
         }
         Expr::Eq(expr1, expr2, end, len) => {
             match (
-                transpiler_expr(expr1, line, sentence, variable, method)?,
-                transpiler_expr(expr2, line, sentence, variable, method)?,
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
             ) {
                 (Value::F(left), Value::F(right)) | (Value::B(left), Value::B(right)) => {
                     Ok(Value::B(format!(
@@ -189,8 +211,8 @@ pub fn transpiler_expr<'a>(
         }
         Expr::Ne(expr1, expr2, end, len) => {
             match (
-                transpiler_expr(expr1, line, sentence, variable, method)?,
-                transpiler_expr(expr2, line, sentence, variable, method)?,
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
             ) {
                 (Value::F(left), Value::F(right)) | (Value::B(left), Value::B(right)) => {
                     Ok(Value::B(format!(
@@ -210,8 +232,8 @@ pub fn transpiler_expr<'a>(
         }
         Expr::Lt(expr1, expr2, ..) => {
             match (
-                transpiler_expr(expr1, line, sentence, variable, method)?,
-                transpiler_expr(expr2, line, sentence, variable, method)?,
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
             ) {
                 (Value::F(left), Value::F(right)) => Ok(Value::B(format!(
                     "{} < {}",
@@ -242,8 +264,8 @@ pub fn transpiler_expr<'a>(
         }
         Expr::Gt(expr1, expr2, ..) => {
             match (
-                transpiler_expr(expr1, line, sentence, variable, method)?,
-                transpiler_expr(expr2, line, sentence, variable, method)?,
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
             ) {
                 (Value::F(left), Value::F(right)) => Ok(Value::B(format!(
                     "{} > {}",
@@ -274,8 +296,8 @@ pub fn transpiler_expr<'a>(
         }
         Expr::And(expr1, expr2, ..) => {
             match (
-                transpiler_expr(expr1, line, sentence, variable, method)?,
-                transpiler_expr(expr2, line, sentence, variable, method)?,
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
             ) {
                 (Value::B(left), Value::B(right)) => Ok(Value::B(format!(
                     "{} && {}",
@@ -306,8 +328,8 @@ pub fn transpiler_expr<'a>(
         }
         Expr::Or(expr1, expr2, ..) => {
             match (
-                transpiler_expr(expr1, line, sentence, variable, method)?,
-                transpiler_expr(expr2, line, sentence, variable, method)?,
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
             ) {
                 (Value::B(left), Value::B(right)) => Ok(Value::B(format!(
                     "{} || {}",
@@ -352,6 +374,218 @@ pub fn transpiler_expr<'a>(
             method.insert("color".to_string());
             Ok(Value::F("draw.color()".to_string()))
         }
+        Expr::PenSize(..) => {
+            method.insert("pen_size".to_string());
+            Ok(Value::F("draw.pen_size()".to_string()))
+        }
+        Expr::FillColor(..) => {
+            method.insert("fill_color".to_string());
+            Ok(Value::F("draw.fill_color()".to_string()))
+        }
+        Expr::RepCount(end, len) => match variable.get(REPCOUNT) {
+            Some(false) => Ok(Value::F(REPCOUNT.to_string())),
+            _ => Err(match_err(
+                sentence.to_string(),
+                line,
+                "UnDefinedRepCount".to_string(),
+                *end,
+                *len,
+            )),
+        },
+        Expr::Call(name, args, end, len) => {
+            let mut hoisted: Vec<String> = Vec::new();
+            let mut args_result: Vec<String> = Vec::new();
+            for arg in args.iter() {
+                match transpiler_expr(arg, line, sentence, variable, method, ast)? {
+                    Value::F(num) if has_call(&num) => {
+                        let name = format!("arg{}", args_result.len());
+                        hoisted.push(format!("let {} = {};", name, num));
+                        args_result.push(name);
+                    }
+                    Value::F(num) => args_result.push(num),
+                    Value::B(_) => {
+                        let (end, len) = get_end_len(arg);
+                        return Err(match_err(
+                            sentence.to_string(),
+                            line,
+                            "UnexpectedBooleanType".to_string(),
+                            end,
+                            len,
+                        ));
+                    }
+                }
+            }
+
+            method.insert("call".to_string());
+            let mut call = format!(
+                "{}?.ok_or_else(|| miette!({:?}))?",
+                call(name, &args_result, ast),
+                format!("{} (process.rs): {}", name, STOP_ERROR)
+            );
+            if !hoisted.is_empty() {
+                call = format!("{{ {} {} }}", hoisted.join(" "), call);
+            }
+            match output_type(ast, name) {
+                Some(true) => Ok(Value::B(call)),
+                Some(false) => Ok(Value::F(call)),
+                None => Err(match_err(
+                    sentence.to_string(),
+                    line,
+                    "MissingOutput".to_string(),
+                    *end,
+                    *len,
+                )),
+            }
+        }
+        Expr::Sin(value, ..)
+        | Expr::Cos(value, ..)
+        | Expr::Tan(value, ..)
+        | Expr::ArcTan(value, ..)
+        | Expr::Sqrt(value, ..)
+        | Expr::Abs(value, ..)
+        | Expr::Round(value, ..)
+        | Expr::Int(value, ..) => {
+            let num = match transpiler_expr(value, line, sentence, variable, method, ast)? {
+                Value::F(num) => num,
+                Value::B(_) => {
+                    let (end, len) = get_end_len(value);
+                    return Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "UnexpectedBooleanType".to_string(),
+                        end,
+                        len,
+                    ));
+                }
+            };
+
+            Ok(Value::F(match expr {
+                Expr::Sin(..) => format!("f32::sin(f32::to_radians({}))", num),
+                Expr::Cos(..) => format!("f32::cos(f32::to_radians({}))", num),
+                Expr::Tan(..) => format!("f32::tan(f32::to_radians({}))", num),
+                Expr::ArcTan(..) => format!("f32::to_degrees(f32::atan({}))", num),
+                Expr::Sqrt(..) => {
+                    method.insert("sqrt".to_string());
+                    format!("draw.sqrt({})?", num)
+                }
+                Expr::Abs(..) => format!("f32::abs({})", num),
+                Expr::Round(..) => format!("f32::round({})", num),
+                _ => format!("f32::trunc({})", num),
+            }))
+        }
+        Expr::Power(expr1, expr2, ..)
+        | Expr::Mod(expr1, expr2, ..)
+        | Expr::Towards(expr1, expr2, ..) => {
+            match (
+                transpiler_expr(expr1, line, sentence, variable, method, ast)?,
+                transpiler_expr(expr2, line, sentence, variable, method, ast)?,
+            ) {
+                (Value::F(left), Value::F(right)) => match expr {
+                    Expr::Power(..) => {
+                        method.insert("power".to_string());
+                        Ok(Value::F(format!("draw.power({}, {})?", left, right)))
+                    }
+                    Expr::Towards(..) => {
+                        method.insert("towards".to_string());
+                        Ok(Value::F(format!("draw.towards({}, {})", left, right)))
+                    }
+                    _ => {
+                        method.insert("modulo".to_string());
+                        Ok(Value::F(format!("draw.modulo({}, {})?", left, right)))
+                    }
+                },
+                (Value::B(_), _) => {
+                    let (end, len) = get_end_len(expr1);
+                    Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "UnexpectedBooleanType".to_string(),
+                        end,
+                        len,
+                    ))
+                }
+                _ => {
+                    let (end, len) = get_end_len(expr2);
+                    Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "UnexpectedBooleanType".to_string(),
+                        end,
+                        len,
+                    ))
+                }
+            }
+        }
+        Expr::Random(value, ..) => {
+            let num = match transpiler_expr(value, line, sentence, variable, method, ast)? {
+                Value::F(num) => num,
+                Value::B(_) => {
+                    let (end, len) = get_end_len(value);
+                    return Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "UnexpectedBooleanType".to_string(),
+                        end,
+                        len,
+                    ));
+                }
+            };
+
+            method.insert("next_random".to_string());
+            method.insert("random".to_string());
+            if has_call(&num) {
+                Ok(Value::F(format!(
+                    "({{ let arg0 = {}; draw.random(arg0)? }})",
+                    num
+                )))
+            } else {
+                Ok(Value::F(format!("draw.random({})?", num)))
+            }
+        }
+        Expr::RandomFloat(..) => {
+            method.insert("next_random".to_string());
+            method.insert("random_float".to_string());
+            Ok(Value::F("draw.random_float()".to_string()))
+        }
+        Expr::Pick(args, end, len) => {
+            let mut arms: Vec<String> = Vec::new();
+            let mut is_bool = None;
+            for (idx, arg) in args.iter().enumerate() {
+                let (value, bool) =
+                    match transpiler_expr(arg, line, sentence, variable, method, ast)? {
+                        Value::F(num) => (num, false),
+                        Value::B(bool) => (bool, true),
+                    };
+                if is_bool.is_some_and(|is_bool| is_bool != bool) {
+                    return Err(match_err(
+                        sentence.to_string(),
+                        line,
+                        "UnmatchedExprType".to_string(),
+                        *end,
+                        *len,
+                    ));
+                }
+                is_bool = Some(bool);
+
+                if idx + 1 == args.len() {
+                    arms.push(format!("_ => {}", value));
+                } else {
+                    arms.push(format!("{} => {}", idx, value));
+                }
+            }
+
+            method.insert("next_random".to_string());
+            method.insert("random".to_string());
+            let pick = format!(
+                "match draw.random({}.0)? as usize {{ {} }}",
+                args.len(),
+                arms.join(", ")
+            );
+            match is_bool {
+                Some(true) => Ok(Value::B(pick)),
+                _ => Ok(Value::F(pick)),
+            }
+        }
         _ => unreachable!(),
     }
 }
@@ -361,6 +595,7 @@ pub fn check_formula(input: String) -> String {
         || input.contains('-')
         || input.contains('*')
         || input.contains('/')
+        || input.contains('%')
         || input.contains("==")
         || input.contains("!=")
         || input.contains('<')
@@ -385,6 +620,24 @@ pub fn get_end_len(expr: &Expr) -> (usize, usize) {
         | Expr::YCor(end, len)
         | Expr::Heading(end, len)
         | Expr::Color(end, len)
+        | Expr::PenSize(end, len)
+        | Expr::FillColor(end, len)
+        | Expr::RepCount(end, len)
+        | Expr::Call(.., end, len)
+        | Expr::Sin(.., end, len)
+        | Expr::Cos(.., end, len)
+        | Expr::Tan(.., end, len)
+        | Expr::ArcTan(.., end, len)
+        | Expr::Sqrt(.., end, len)
+        | Expr::Abs(.., end, len)
+        | Expr::Round(.., end, len)
+        | Expr::Int(.., end, len)
+        | Expr::Power(.., end, len)
+        | Expr::Mod(.., end, len)
+        | Expr::Towards(.., end, len)
+        | Expr::Random(.., end, len)
+        | Expr::RandomFloat(end, len)
+        | Expr::Pick(.., end, len)
         | Expr::Eq(.., end, len)
         | Expr::Ne(.., end, len)
         | Expr::Lt(.., end, len)
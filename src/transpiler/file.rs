@@ -1,3 +1,4 @@
+use crate::generation::Canvas;
 use miette::{IntoDiagnostic, Result};
 use std::collections::HashSet;
 use std::fs;
@@ -17,6 +18,9 @@ impl DrawMethod {
     }
 
     pub fn insert(&mut self, key: String) {
+        for helper in helpers(&key) {
+            self.insert(helper.to_string());
+        }
         self.set.insert(key);
     }
 
@@ -25,6 +29,45 @@ impl DrawMethod {
     }
 }
 
+/// The private methods a draw method calls, emitted along with it.
+fn helpers(key: &str) -> &'static [&'static str] {
+    match key {
+        "arc" => &["inside", "copies"],
+        "set_pos" => &["inside", "line", "wrap_line"],
+        "set_x" | "set_y" => &["jump"],
+        "jump" => &["inside"],
+        "wrap_line" => &["line"],
+        "color" | "fill_color" => &["palette_index"],
+        "num" | "boolean" => &["value"],
+        "set_num" | "set_bool" => &["frame"],
+        _ => &[],
+    }
+}
+
+/// Keeps the lines of a template between `// if <keys>` and `// else` or `// end` when the
+/// program uses one of the draw methods named by the keys, and the lines between `// else`
+/// and `// end` when it uses none of them, so the generated crate has no dead code.
+fn select(template: &str, map: &DrawMethod) -> String {
+    let mut keep = vec![true];
+    let mut result = String::new();
+    for line in template.split_inclusive('\n') {
+        let marker = line.trim();
+        if let Some(keys) = marker.strip_prefix("// if ") {
+            let used = keys.split_whitespace().any(|key| map.set.contains(key));
+            keep.push(keep.iter().all(|&keep| keep) && used);
+        } else if marker == "// else" {
+            let used = keep.pop().unwrap_or_default();
+            keep.push(keep.iter().all(|&keep| keep) && !used);
+        } else if marker == "// end" {
+            keep.pop();
+        } else if keep.iter().all(|&keep| keep) && !(marker.is_empty() && result.ends_with("\n\n"))
+        {
+            result.push_str(line);
+        }
+    }
+    result
+}
+
 fn read_file_to_string(file_path: &str) -> Result<String> {
     let mut file = File::open(file_path).into_diagnostic()?;
     let mut contents = String::new();
@@ -34,14 +77,19 @@ fn read_file_to_string(file_path: &str) -> Result<String> {
 }
 
 fn export_draw_file(map: &DrawMethod, path: &Path) -> Result<()> {
-    let mut draw = read_file_to_string("template/draw.rs.template")?;
+    let mut draw = select(&read_file_to_string("template/draw.rs.template")?, map);
 
-    let mut list = vec![read_file_to_string("template/draw_impl/new.template")?];
+    let mut list = vec![
+        select(
+            &read_file_to_string("template/draw_impl/new.template")?,
+            map,
+        ),
+        read_file_to_string("template/draw_impl/boundary.template")?,
+    ];
 
     for key in map.keys() {
-        list.push(read_file_to_string(&format!(
-            "template/draw_impl/{key}.template"
-        ))?);
+        let method = read_file_to_string(&format!("template/draw_impl/{key}.template"))?;
+        list.push(select(&method, map));
     }
 
     draw.push_str(&format!("\nimpl<'a> Draw<'a> {{\n{}}}", list.join("\n")));
@@ -54,10 +102,16 @@ fn export_draw_file(map: &DrawMethod, path: &Path) -> Result<()> {
     Ok(())
 }
 
-pub fn export_main_file(path: &Path, width: u32, height: u32) -> Result<()> {
+pub fn export_main_file(path: &Path, canvas: &Canvas, seed: u64) -> Result<()> {
     let main = read_file_to_string("template/main.rs.template")?
-        .replace("{WIDTH}", &width.to_string())
-        .replace("{HEIGHT}", &height.to_string())
+        .replace("{WIDTH}", &canvas.width.to_string())
+        .replace("{HEIGHT}", &canvas.height.to_string())
+        .replace("{ORIGIN_X}", &format!("{:?}", canvas.origin.0))
+        .replace("{ORIGIN_Y}", &format!("{:?}", canvas.origin.1))
+        .replace("{HEADING}", &format!("{:?}", canvas.heading))
+        .replace("{BOUNDARY}", &format!("{:?}", canvas.boundary))
+        .replace("{AUTO_SIZE}", &format!("{:?}", canvas.auto_size))
+        .replace("{SEED}", &seed.to_string())
         .replace("{FILENAME}", &path.display().to_string());
 
     File::create(format!("{}/src/main.rs", path.display()))
@@ -68,6 +122,17 @@ pub fn export_main_file(path: &Path, width: u32, height: u32) -> Result<()> {
     Ok(())
 }
 
+pub fn export_image_file(map: &DrawMethod, path: &Path) -> Result<()> {
+    let image = select(&read_file_to_string("template/image.rs.template")?, map);
+
+    File::create(format!("{}/src/image.rs", path.display()))
+        .into_diagnostic()?
+        .write_all(image.as_bytes())
+        .into_diagnostic()?;
+
+    Ok(())
+}
+
 pub fn export_cargo_file(path: &Path) -> Result<()> {
     let main = read_file_to_string("template/Cargo.toml.template")?
         .replace("{FILENAME}", &path.display().to_string());
@@ -83,8 +148,8 @@ pub fn export_cargo_file(path: &Path) -> Result<()> {
 pub fn export_file(
     path: &PathBuf,
     map: &DrawMethod,
-    width: u32,
-    height: u32,
+    canvas: &Canvas,
+    seed: u64,
     result: &[String],
 ) -> Result<()> {
     fs::create_dir(path).into_diagnostic()?;
@@ -93,8 +158,9 @@ pub fn export_file(
 
     fs::create_dir(format!("{}/src", path.display())).into_diagnostic()?;
 
-    export_main_file(path, width, height)?;
+    export_main_file(path, canvas, seed)?;
     export_draw_file(map, path)?;
+    export_image_file(map, path)?;
 
     File::create(format!("{}/src/process.rs", path.display()))
         .into_diagnostic()?
@@ -1,44 +1,296 @@
-use crate::ast::structs::{Assign, Function, Stmt};
-use crate::transpiler::{err::TranspilerError, file::DrawMethod, stmt::transpiler_stmt};
+use crate::ast::structs::{Assign, Expr, Function, Stmt};
+use crate::transpiler::{
+    err::TranspilerError, expr::var_name, file::DrawMethod, stmt::transpiler_stmt,
+};
 use std::collections::HashMap;
 
+pub const STOP_ERROR: &str = "Function finished without `OUTPUT`";
+
 pub fn transpile_func<'a>(
-    args: &[Assign],
-    stmt_list: &Vec<Stmt>,
+    stmt_list: &[Stmt],
     name: &str,
     file: &[String],
     method: &mut DrawMethod,
     ast: &Function,
+    globals: &HashMap<String, bool>,
 ) -> Result<String, TranspilerError<'a>> {
+    // Variables have no scope, so every function may read any variable the program makes.
+    let mut variable: HashMap<String, bool> = globals
+        .iter()
+        .map(|(name, bool)| (var_name(name), *bool))
+        .collect();
+
     if name.is_empty() {
+        let body = transpiler_stmt(stmt_list, file, method, &mut variable, 1, ast, None)?;
         Ok(format!(
-            "pub fn process_svg(draw: &mut Draw) -> Result<()> {{\n{}\tOk(())\n}}\n",
-            transpiler_stmt(stmt_list, file, method, &mut HashMap::new(), 1, ast)?
+            "pub fn process_svg({}: &mut Draw) -> Result<()> {{\n{}\tOk(())\n}}\n",
+            draw_param(&body),
+            body
         ))
     } else {
-        let args: Vec<String> = args
-            .iter()
-            .map(|x| {
-                if let Assign::Var(name, ..) = &x {
-                    format!("{}: f32", name)
-                } else {
-                    unreachable!();
-                }
-            })
-            .collect();
+        let output = output_type(ast, name);
+        let (result, end) = match output {
+            Some(true) => ("Option<bool>", "\tOk(None)\n"),
+            Some(false) => ("Option<f32>", "\tOk(None)\n"),
+            None => ("()", "\tOk(())\n"),
+        };
+        let end = match always_returns(stmt_list) {
+            true => "",
+            false => end,
+        };
+
+        for (name, bool) in ast.get_args_by_name(name) {
+            variable.insert(var_name(&name), bool);
+        }
+        let body = transpiler_stmt(stmt_list, file, method, &mut variable, 1, ast, output)?;
 
         Ok(format!(
-            "fn {}(draw: &mut Draw, {}) -> Result<()> {{\n{}\tOk(())\n}}\n",
+            "fn {}({}: &mut Draw) -> Result<{}> {{\n{}{}}}\n",
             name,
-            args.join(", "),
-            transpiler_stmt(
-                stmt_list,
-                file,
-                method,
-                &mut ast.get_args_by_name(name),
-                1,
-                ast
-            )?
+            draw_param(&body),
+            result,
+            body,
+            end
         ))
     }
 }
+
+/// Calls procedure `name` through `Draw::call`, which gives it a frame holding its
+/// arguments, like `Variable::push_scope`.
+pub fn call(name: &str, args: &[String], ast: &Function) -> String {
+    let names = ast.get(name).map_or(&[][..], |func| &func.args);
+    let args: Vec<String> = names
+        .iter()
+        .zip(args)
+        .map(|(assign, value)| match assign {
+            Assign::Var(name, ..) => format!("({:?}, {})", name, value),
+            _ => unreachable!(),
+        })
+        .collect();
+
+    format!("draw.call(&[{}], {})", args.join(", "), name)
+}
+
+/// Whether the transpiled `body` calls procedure `name`, as written by `call`.
+pub fn calls(body: &str, name: &str) -> bool {
+    body.contains(&format!("], {name})"))
+}
+
+/// The type of every variable the program makes, `true` for a boolean. Variables have no
+/// scope, so a procedure can read the variables of the main program, and the arguments of
+/// the procedures that call it. A name made with both types keeps the type of the first
+/// function that makes it, in name order, and reading it as the other type fails when the
+/// generated program runs.
+pub fn variable_types(ast: &Function) -> HashMap<String, bool> {
+    let mut names: Vec<&String> = ast.get_all().keys().collect();
+    names.sort();
+
+    let mut types: HashMap<String, bool> = HashMap::new();
+    loop {
+        let known = types.len();
+        for name in &names {
+            let mut variable = types.clone();
+            variable.extend(ast.get_args_by_name(name));
+            if let Some(func) = ast.get(name) {
+                collect_output(
+                    &func.stmt_list,
+                    ast,
+                    &mut variable,
+                    &mut vec![],
+                    &mut vec![],
+                );
+            }
+            for (name, bool) in variable {
+                types.entry(name).or_insert(bool);
+            }
+        }
+        if types.len() == known {
+            return types;
+        }
+    }
+}
+
+/// Names the `draw` parameter `_draw` when the body never uses it.
+fn draw_param(body: &str) -> &'static str {
+    match body.contains("draw.") {
+        true => "draw",
+        false => "_draw",
+    }
+}
+
+/// Whether `REPCOUNT` is read by these statements, not counting nested `REPEAT` bodies,
+/// which have their own counter.
+pub fn uses_repcount(stmt_list: &[Stmt]) -> bool {
+    stmt_list.iter().any(|stmt| match stmt {
+        Stmt::Repeat(expr, ..) => expr_uses_repcount(expr),
+        Stmt::If(expr, stmt, _) | Stmt::While(expr, stmt, _) => {
+            expr_uses_repcount(expr) || uses_repcount(stmt)
+        }
+        Stmt::IfElse(expr, then_stmt, else_stmt, _) => {
+            expr_uses_repcount(expr) || uses_repcount(then_stmt) || uses_repcount(else_stmt)
+        }
+        Stmt::Make(_, expr, _)
+        | Stmt::AddAssign(_, expr, _)
+        | Stmt::Forward(expr, _)
+        | Stmt::Back(expr, _)
+        | Stmt::Left(expr, _)
+        | Stmt::Right(expr, _)
+        | Stmt::SetPenColor(expr, _)
+        | Stmt::SetPenSize(expr, _)
+        | Stmt::SetBackground(expr, _)
+        | Stmt::SetFillColor(expr, _)
+        | Stmt::SetLabelHeight(expr, _)
+        | Stmt::Turn(expr, _)
+        | Stmt::Circle(expr, _)
+        | Stmt::SetHeading(expr, _)
+        | Stmt::SetX(expr, _)
+        | Stmt::SetY(expr, _)
+        | Stmt::Output(expr, _)
+        | Stmt::Rerandom(expr, _) => expr_uses_repcount(expr),
+        Stmt::Arc(expr1, expr2, _) | Stmt::SetPos(expr1, expr2, _) => {
+            expr_uses_repcount(expr1) || expr_uses_repcount(expr2)
+        }
+        Stmt::SetPenRgb(red, green, blue, _) | Stmt::SetBackgroundRgb(red, green, blue, _) => {
+            [red, green, blue]
+                .iter()
+                .any(|expr| expr_uses_repcount(expr))
+        }
+        Stmt::SetPalette(index, red, green, blue, _) => [index, red, green, blue]
+            .iter()
+            .any(|expr| expr_uses_repcount(expr)),
+        Stmt::Func(_, args, _) => args.iter().any(expr_uses_repcount),
+        _ => false,
+    })
+}
+
+fn expr_uses_repcount(expr: &Expr) -> bool {
+    match expr {
+        Expr::RepCount(..) => true,
+        Expr::Add(expr1, expr2, ..)
+        | Expr::Sub(expr1, expr2, ..)
+        | Expr::Mul(expr1, expr2, ..)
+        | Expr::Div(expr1, expr2, ..)
+        | Expr::Eq(expr1, expr2, ..)
+        | Expr::Ne(expr1, expr2, ..)
+        | Expr::Lt(expr1, expr2, ..)
+        | Expr::Gt(expr1, expr2, ..)
+        | Expr::And(expr1, expr2, ..)
+        | Expr::Or(expr1, expr2, ..)
+        | Expr::Power(expr1, expr2, ..)
+        | Expr::Mod(expr1, expr2, ..)
+        | Expr::Towards(expr1, expr2, ..) => expr_uses_repcount(expr1) || expr_uses_repcount(expr2),
+        Expr::Sin(value, ..)
+        | Expr::Cos(value, ..)
+        | Expr::Tan(value, ..)
+        | Expr::ArcTan(value, ..)
+        | Expr::Sqrt(value, ..)
+        | Expr::Abs(value, ..)
+        | Expr::Round(value, ..)
+        | Expr::Int(value, ..)
+        | Expr::Random(value, ..) => expr_uses_repcount(value),
+        Expr::Call(_, args, ..) | Expr::Pick(args, ..) => args.iter().any(expr_uses_repcount),
+        _ => false,
+    }
+}
+
+/// The type each `OUTPUT` of these statements produces, in order. Variables are typed the
+/// same way `transpiler_stmt` records them, so `OUTPUT :flag` has the type of the last
+/// `MAKE "flag` before it. `None` marks an output whose type depends on a procedure still
+/// being typed.
+fn collect_output(
+    stmt_list: &[Stmt],
+    ast: &Function,
+    variable: &mut HashMap<String, bool>,
+    visiting: &mut Vec<String>,
+    list: &mut Vec<Option<bool>>,
+) {
+    for stmt in stmt_list {
+        match stmt {
+            Stmt::Output(expr, ..) => list.push(expr_type(expr, ast, variable, visiting)),
+            Stmt::Make(assign, expr, ..) => {
+                if let (Assign::Var(name, ..), Some(kind)) =
+                    (assign.as_ref(), expr_type(expr, ast, variable, visiting))
+                {
+                    variable.insert(name.to_string(), kind);
+                }
+            }
+            Stmt::Local(assign, ..) => {
+                if let Assign::Var(name, ..) = assign.as_ref() {
+                    variable.remove(name);
+                }
+            }
+            Stmt::If(_, stmt, _) | Stmt::While(_, stmt, _) | Stmt::Repeat(_, stmt, _) => {
+                collect_output(stmt, ast, variable, visiting, list)
+            }
+            Stmt::IfElse(_, then_stmt, else_stmt, _) => {
+                collect_output(then_stmt, ast, variable, visiting, list);
+                collect_output(else_stmt, ast, variable, visiting, list);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn expr_type(
+    expr: &Expr,
+    ast: &Function,
+    variable: &HashMap<String, bool>,
+    visiting: &mut Vec<String>,
+) -> Option<bool> {
+    match expr {
+        Expr::Boolean(..)
+        | Expr::Eq(..)
+        | Expr::Ne(..)
+        | Expr::Lt(..)
+        | Expr::Gt(..)
+        | Expr::And(..)
+        | Expr::Or(..) => Some(true),
+        Expr::Var(name, ..) => variable.get(name).copied(),
+        Expr::Call(name, ..) if visiting.contains(name) => None,
+        Expr::Call(name, ..) => output_type_of(ast, name, visiting),
+        _ => Some(false),
+    }
+}
+
+/// The type procedure `name` outputs, `Some(true)` for a boolean, or `None` if it never
+/// uses `OUTPUT`. The first `OUTPUT` decides, `transpiler_stmt` rejects the others if they
+/// disagree.
+pub fn output_type(ast: &Function, name: &str) -> Option<bool> {
+    output_type_of(ast, name, &mut vec![])
+}
+
+fn output_type_of(ast: &Function, name: &str, visiting: &mut Vec<String>) -> Option<bool> {
+    let func = ast.get(name)?;
+    let mut list = vec![];
+
+    visiting.push(name.to_string());
+    collect_output(
+        &func.stmt_list,
+        ast,
+        &mut ast.get_args_by_name(name),
+        visiting,
+        &mut list,
+    );
+    visiting.pop();
+
+    match list.is_empty() {
+        true => None,
+        false => Some(list.into_iter().flatten().next().unwrap_or(false)),
+    }
+}
+
+/// Whether these statements always end in `OUTPUT` or `STOP`, so nothing after them is
+/// reachable.
+fn always_returns(stmt_list: &[Stmt]) -> bool {
+    match stmt_list
+        .iter()
+        .rev()
+        .find(|stmt| !matches!(stmt, Stmt::Comments(..)))
+    {
+        Some(Stmt::Output(..) | Stmt::Stop(..)) => true,
+        Some(Stmt::IfElse(_, then_stmt, else_stmt, _)) => {
+            always_returns(then_stmt) && always_returns(else_stmt)
+        }
+        _ => false,
+    }
+}
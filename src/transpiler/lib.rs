@@ -1,37 +1,72 @@
 use crate::ast::structs::Function;
+use crate::generation::Canvas;
 use crate::transpiler::{
     file::{export_file, DrawMethod},
-    func::transpile_func,
+    func::{calls, transpile_func, variable_types},
 };
 
 use miette::Result;
+use std::collections::HashMap;
 use std::path::PathBuf;
 
+fn transpile(ast: &Function, file: &[String], method: &mut DrawMethod) -> Result<Vec<String>> {
+    let mut result = vec!["use crate::draw::Draw;".to_string(), String::new()];
+
+    let globals = variable_types(ast);
+    let mut names: Vec<&String> = ast.get_all().keys().collect();
+    names.sort();
+
+    let mut funcs = HashMap::new();
+    for name in &names {
+        let func = &ast.get_all()[*name];
+        let mut used = DrawMethod::new();
+        let body = transpile_func(&func.stmt_list, name, file, &mut used, ast, &globals)?;
+        funcs.insert(name.as_str(), (body, used));
+    }
+
+    // Every procedure is checked, but only the main program and the procedures it can reach
+    // are written, along with the draw methods they use, so the crate has no dead code.
+    let mut reached = vec![""];
+    let mut idx = 0;
+    while let Some(&caller) = reached.get(idx) {
+        for name in &names {
+            if !reached.contains(&name.as_str()) && calls(&funcs[caller].0, name) {
+                reached.push(name);
+            }
+        }
+        idx += 1;
+    }
+    for name in reached {
+        let (body, used) = funcs.remove(name).expect("Expected a transpiled function");
+        method.set.extend(used.set);
+        result.push(body);
+    }
+
+    // Only a procedure call used as a value raises an error of its own.
+    result[1] = match result.iter().any(|func| func.contains("miette!")) {
+        true => "use miette::{miette, Result};\n".to_string(),
+        false => "use miette::Result;\n".to_string(),
+    };
+
+    Ok(result)
+}
+
+/// Runs the transpiler's type checks without writing anything.
+pub fn check_rust(ast: &Function, file: &[String]) -> Result<()> {
+    transpile(ast, file, &mut DrawMethod::new()).map(|_| ())
+}
+
 pub fn transpiler_rust(
     path: &PathBuf,
     ast: Function,
     file: &[String],
-    width: u32,
-    height: u32,
+    canvas: &Canvas,
+    seed: u64,
 ) -> Result<()> {
     let mut method = DrawMethod::new();
-    let mut result = vec![
-        "use crate::draw::Draw;".to_string(),
-        "use miette::Result;\n".to_string(),
-    ];
-
-    for (name, func) in ast.get_all() {
-        result.push(transpile_func(
-            &func.args,
-            &func.stmt_list,
-            name,
-            file,
-            &mut method,
-            &ast,
-        )?);
-    }
+    let result = transpile(&ast, file, &mut method)?;
 
-    export_file(path, &method, width, height, &result)?;
+    export_file(path, &method, canvas, seed, &result)?;
 
     Ok(())
 }
@@ -1,4 +1,4 @@
-pub use lib::transpiler_rust;
+pub use lib::{check_rust, transpiler_rust};
 
 mod err;
 mod expr;
@@ -1,68 +1,138 @@
-use crate::ast::structs::{Assign, DeclName, Function, Stmt};
+use crate::ast::structs::{Assign, DeclName, Expr, Function, Stmt};
 use crate::transpiler::{
     err::{match_err, TranspilerError},
-    expr::{get_end_len, transpiler_expr, Value},
+    expr::{check_formula, get_end_len, has_call, transpiler_expr, var_name, Value, REPCOUNT},
     file::DrawMethod,
+    func::{call, uses_repcount},
 };
 use std::collections::HashMap;
 
 pub fn transpiler_stmt<'a>(
-    stmt_list: &Vec<Stmt>,
+    stmt_list: &[Stmt],
     file: &[String],
     method: &mut DrawMethod,
     variable: &mut HashMap<String, bool>,
     tab: i32,
     ast: &Function,
+    output: Option<bool>,
 ) -> Result<String, TranspilerError<'a>> {
     let mut result: Vec<String> = Vec::new();
 
     for stmt in stmt_list {
         match stmt {
             Stmt::If(expr, stmt, line) => {
-                let condition = match transpiler_expr(expr, *line, &file[*line], variable, method)?
-                {
-                    Value::B(bool) => bool,
-                    _ => {
-                        let (end, len) = get_end_len(expr);
-                        Err(match_err(
-                            file[*line].to_string(),
-                            *line,
-                            "UnexpectedNumberType".to_string(),
-                            end,
-                            len,
-                        ))?
-                    }
-                };
+                let condition =
+                    match transpiler_expr(expr, *line, &file[*line], variable, method, ast)? {
+                        Value::B(bool) => bool,
+                        _ => {
+                            let (end, len) = get_end_len(expr);
+                            Err(match_err(
+                                file[*line].to_string(),
+                                *line,
+                                "UnexpectedNumberType".to_string(),
+                                end,
+                                len,
+                            ))?
+                        }
+                    };
 
                 result.push(format!(
                     "{}if {} {{\n{}{}}}\n",
                     set_tab(tab),
                     condition,
-                    transpiler_stmt(stmt, file, method, variable, tab + 1, ast)?,
+                    transpiler_stmt(stmt, file, method, variable, tab + 1, ast, output)?,
+                    set_tab(tab)
+                ));
+            }
+            Stmt::IfElse(expr, then_stmt, else_stmt, line) => {
+                let condition =
+                    match transpiler_expr(expr, *line, &file[*line], variable, method, ast)? {
+                        Value::B(bool) => bool,
+                        _ => {
+                            let (end, len) = get_end_len(expr);
+                            Err(match_err(
+                                file[*line].to_string(),
+                                *line,
+                                "UnexpectedNumberType".to_string(),
+                                end,
+                                len,
+                            ))?
+                        }
+                    };
+
+                result.push(format!(
+                    "{}if {} {{\n{}{}}} else {{\n{}{}}}\n",
+                    set_tab(tab),
+                    condition,
+                    transpiler_stmt(then_stmt, file, method, variable, tab + 1, ast, output)?,
+                    set_tab(tab),
+                    transpiler_stmt(else_stmt, file, method, variable, tab + 1, ast, output)?,
                     set_tab(tab)
                 ));
             }
             Stmt::While(expr, stmt, line) => {
-                let condition = match transpiler_expr(expr, *line, &file[*line], variable, method)?
+                let condition =
+                    match transpiler_expr(expr, *line, &file[*line], variable, method, ast)? {
+                        Value::B(bool) => bool,
+                        _ => {
+                            let (end, len) = get_end_len(expr);
+                            Err(match_err(
+                                file[*line].to_string(),
+                                *line,
+                                "UnexpectedNumberType".to_string(),
+                                end,
+                                len,
+                            ))?
+                        }
+                    };
+
+                result.push(format!(
+                    "{}while {} {{\n{}{}}}\n",
+                    set_tab(tab),
+                    condition,
+                    transpiler_stmt(stmt, file, method, variable, tab + 1, ast, output)?,
+                    set_tab(tab)
+                ));
+            }
+            Stmt::Repeat(expr, stmt, line) => {
+                let count = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
                 {
-                    Value::B(bool) => bool,
+                    Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
                         Err(match_err(
                             file[*line].to_string(),
                             *line,
-                            "UnexpectedNumberType".to_string(),
+                            "UnexpectedBooleanType".to_string(),
                             end,
                             len,
                         ))?
                     }
                 };
 
+                let previous = variable.insert(REPCOUNT.to_string(), false);
+                let body = transpiler_stmt(stmt, file, method, variable, tab + 1, ast, output)?;
+                match previous {
+                    Some(value) => variable.insert(REPCOUNT.to_string(), value),
+                    None => variable.remove(REPCOUNT),
+                };
+
+                let (counter, range) = match uses_repcount(stmt) {
+                    true => (REPCOUNT, "(1..=count).map(|i| i as f32)"),
+                    false => ("_", "1..=count"),
+                };
+
+                method.insert("repeat_count".to_string());
                 result.push(format!(
-                    "{}while {} {{\n{}{}}}\n",
+                    "{}{}for {} in {} {{\n{}{}}}\n",
+                    draw_call(tab, count, |count| format!(
+                        "let count = draw.repeat_count({})?;",
+                        count
+                    )),
                     set_tab(tab),
-                    condition,
-                    transpiler_stmt(stmt, file, method, variable, tab + 1, ast)?,
+                    counter,
+                    range,
+                    body,
                     set_tab(tab)
                 ));
             }
@@ -72,22 +142,17 @@ pub fn transpiler_stmt<'a>(
                     _ => unreachable!(),
                 };
 
-                match transpiler_expr(expr, *line, &file[*line], variable, method)? {
-                    Value::F(num) => match variable.get(name) {
-                        Some(true) | None => {
-                            result.push(format!("{}let mut {} = {};\n", set_tab(tab), name, num));
-                            variable.insert(name.to_string(), false);
-                        }
-                        _ => result.push(format!("{}{} = {};\n", set_tab(tab), name, num)),
-                    },
-                    Value::B(bool) => match variable.get(name) {
-                        Some(false) | None => {
-                            result.push(format!("{}let mut {} = {};\n", set_tab(tab), name, bool));
-                            variable.insert(name.to_string(), true);
-                        }
-                        _ => result.push(format!("{}{} = {};\n", set_tab(tab), name, bool)),
-                    },
-                };
+                let (value, setter, bool) =
+                    match transpiler_expr(expr, *line, &file[*line], variable, method, ast)? {
+                        Value::F(num) => (num, "set_num", false),
+                        Value::B(bool) => (bool, "set_bool", true),
+                    };
+                variable.insert(var_name(name), bool);
+
+                method.insert(setter.to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.{}({:?}, {});", setter, name, value)
+                }));
             }
             Stmt::AddAssign(assign, expr, line) => {
                 let name = match assign.as_ref() {
@@ -95,7 +160,8 @@ pub fn transpiler_stmt<'a>(
                     _ => unreachable!(),
                 };
 
-                let value = match transpiler_expr(expr, *line, &file[*line], variable, method)? {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
                     Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
@@ -109,7 +175,7 @@ pub fn transpiler_stmt<'a>(
                     }
                 };
 
-                match variable.get(name) {
+                match variable.get(&var_name(name)) {
                     Some(true) => {
                         let (end, len) = get_end_len(expr);
                         Err(match_err(
@@ -130,7 +196,18 @@ pub fn transpiler_stmt<'a>(
                             len,
                         ))?
                     }
-                    _ => result.push(format!("{}{} += {};\n", set_tab(tab), name, value)),
+                    _ => {
+                        method.insert("num".to_string());
+                        method.insert("set_num".to_string());
+                        result.push(draw_call(tab, value, |value| {
+                            format!(
+                                "draw.set_num({:?}, draw.num({:?})? + {});",
+                                name,
+                                name,
+                                check_formula(value.to_string())
+                            )
+                        }));
+                    }
                 }
             }
             Stmt::PenUp(..) => {
@@ -142,7 +219,8 @@ pub fn transpiler_stmt<'a>(
                 result.push(format!("{}draw.pen_down();\n", set_tab(tab)));
             }
             Stmt::Forward(expr, line) => {
-                let value = match transpiler_expr(expr, *line, &file[*line], variable, method)? {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
                     Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
@@ -157,10 +235,14 @@ pub fn transpiler_stmt<'a>(
                 };
 
                 method.insert("pen_move".to_string());
-                result.push(format!("{}draw.pen_move(0, {})?;\n", set_tab(tab), value));
+                method.insert("set_pos".to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.pen_move(0.0, {})?;", value)
+                }));
             }
             Stmt::Back(expr, line) => {
-                let value = match transpiler_expr(expr, *line, &file[*line], variable, method)? {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
                     Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
@@ -175,10 +257,14 @@ pub fn transpiler_stmt<'a>(
                 };
 
                 method.insert("pen_move".to_string());
-                result.push(format!("{}draw.pen_move(180, {})?;\n", set_tab(tab), value));
+                method.insert("set_pos".to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.pen_move(180.0, {})?;", value)
+                }));
             }
             Stmt::Left(expr, line) => {
-                let value = match transpiler_expr(expr, *line, &file[*line], variable, method)? {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
                     Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
@@ -193,10 +279,14 @@ pub fn transpiler_stmt<'a>(
                 };
 
                 method.insert("pen_move".to_string());
-                result.push(format!("{}draw.pen_move(-90, {})?;\n", set_tab(tab), value));
+                method.insert("set_pos".to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.pen_move(-90.0, {})?;", value)
+                }));
             }
             Stmt::Right(expr, line) => {
-                let value = match transpiler_expr(expr, *line, &file[*line], variable, method)? {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
                     Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
@@ -211,10 +301,14 @@ pub fn transpiler_stmt<'a>(
                 };
 
                 method.insert("pen_move".to_string());
-                result.push(format!("{}draw.pen_move(90, {})?;\n", set_tab(tab), value));
+                method.insert("set_pos".to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.pen_move(90.0, {})?;", value)
+                }));
             }
             Stmt::SetPenColor(expr, line) => {
-                let value = match transpiler_expr(expr, *line, &file[*line], variable, method)? {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
                     Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
@@ -229,10 +323,98 @@ pub fn transpiler_stmt<'a>(
                 };
 
                 method.insert("set_pen_color".to_string());
-                result.push(format!("{}draw.set_pen_color({})?;\n", set_tab(tab), value));
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.set_pen_color({})?;", value)
+                }));
+            }
+            Stmt::SetPenSize(expr, line) => {
+                let value = transpiler_num(expr, *line, file, variable, method, ast)?;
+
+                method.insert("set_pen_size".to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.set_pen_size({})?;", value)
+                }));
+            }
+            Stmt::Label(text, ..) => {
+                method.insert("label".to_string());
+                result.push(format!("{}draw.label({:?})?;\n", set_tab(tab), text));
+            }
+            Stmt::SetLabelHeight(expr, line) => {
+                let value = transpiler_num(expr, *line, file, variable, method, ast)?;
+
+                method.insert("set_label_height".to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.set_label_height({})?;", value)
+                }));
+            }
+            Stmt::SetFillColor(expr, line) => {
+                let value = transpiler_num(expr, *line, file, variable, method, ast)?;
+
+                method.insert("set_fill_color".to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.set_fill_color({})?;", value)
+                }));
+            }
+            Stmt::PushTurtle(..) => {
+                method.insert("push_turtle".to_string());
+                result.push(format!("{}draw.push_turtle();\n", set_tab(tab)));
+            }
+            Stmt::PopTurtle(..) => {
+                method.insert("pop_turtle".to_string());
+                result.push(format!("{}draw.pop_turtle()?;\n", set_tab(tab)));
+            }
+            Stmt::BeginFill(..) => {
+                method.insert("begin_fill".to_string());
+                result.push(format!("{}draw.begin_fill()?;\n", set_tab(tab)));
+            }
+            Stmt::EndFill(..) => {
+                method.insert("end_fill".to_string());
+                result.push(format!("{}draw.end_fill()?;\n", set_tab(tab)));
+            }
+            Stmt::SetBackground(expr, line) => {
+                let value = transpiler_num(expr, *line, file, variable, method, ast)?;
+
+                method.insert("set_background".to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.set_background({})?;", value)
+                }));
+            }
+            Stmt::SetBackgroundRgb(red, green, blue, line) => {
+                let mut values = Vec::new();
+                for expr in [red, green, blue] {
+                    values.push(transpiler_num(expr, *line, file, variable, method, ast)?);
+                }
+
+                method.insert("set_background_rgb".to_string());
+                result.push(draw_calls(tab, values, |values| {
+                    format!("draw.set_background_rgb({})?;", values.join(", "))
+                }));
+            }
+            Stmt::SetPenRgb(red, green, blue, line) => {
+                let mut values = Vec::new();
+                for expr in [red, green, blue] {
+                    values.push(transpiler_num(expr, *line, file, variable, method, ast)?);
+                }
+
+                method.insert("set_pen_rgb".to_string());
+                result.push(draw_calls(tab, values, |values| {
+                    format!("draw.set_pen_rgb({})?;", values.join(", "))
+                }));
+            }
+            Stmt::SetPalette(index, red, green, blue, line) => {
+                let mut values = Vec::new();
+                for expr in [index, red, green, blue] {
+                    values.push(transpiler_num(expr, *line, file, variable, method, ast)?);
+                }
+
+                method.insert("set_palette".to_string());
+                result.push(draw_calls(tab, values, |values| {
+                    format!("draw.set_palette({})?;", values.join(", "))
+                }));
             }
             Stmt::Turn(expr, line) => {
-                let value = match transpiler_expr(expr, *line, &file[*line], variable, method)? {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
                     Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
@@ -247,10 +429,32 @@ pub fn transpiler_stmt<'a>(
                 };
 
                 method.insert("turn".to_string());
-                result.push(format!("{}draw.turn({})?;\n", set_tab(tab), value));
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.turn({})?;", value)
+                }));
+            }
+            Stmt::Arc(angle, radius, line) => {
+                let mut values = Vec::new();
+                for expr in [angle, radius] {
+                    values.push(transpiler_num(expr, *line, file, variable, method, ast)?);
+                }
+
+                method.insert("arc".to_string());
+                result.push(draw_calls(tab, values, |values| {
+                    format!("draw.arc({})?;", values.join(", "))
+                }));
+            }
+            Stmt::Circle(radius, line) => {
+                let value = transpiler_num(radius, *line, file, variable, method, ast)?;
+
+                method.insert("arc".to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.arc(360.0, {})?;", value)
+                }));
             }
             Stmt::SetHeading(expr, line) => {
-                let value = match transpiler_expr(expr, *line, &file[*line], variable, method)? {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
                     Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
@@ -265,10 +469,13 @@ pub fn transpiler_stmt<'a>(
                 };
 
                 method.insert("set_heading".to_string());
-                result.push(format!("{}draw.set_heading({})?;\n", set_tab(tab), value));
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.set_heading({})?;", value)
+                }));
             }
             Stmt::SetX(expr, line) => {
-                let value = match transpiler_expr(expr, *line, &file[*line], variable, method)? {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
                     Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
@@ -283,10 +490,13 @@ pub fn transpiler_stmt<'a>(
                 };
 
                 method.insert("set_x".to_string());
-                result.push(format!("{}draw.set_x({});\n", set_tab(tab), value));
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.set_x({})?;", value)
+                }));
             }
             Stmt::SetY(expr, line) => {
-                let value = match transpiler_expr(expr, *line, &file[*line], variable, method)? {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
                     Value::F(num) => num,
                     _ => {
                         let (end, len) = get_end_len(expr);
@@ -301,7 +511,42 @@ pub fn transpiler_stmt<'a>(
                 };
 
                 method.insert("set_y".to_string());
-                result.push(format!("{}draw.set_y({});\n", set_tab(tab), value));
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.set_y({})?;", value)
+                }));
+            }
+            Stmt::SetPos(x, y, line) => {
+                let mut values = Vec::new();
+                for expr in [x, y] {
+                    values.push(transpiler_num(expr, *line, file, variable, method, ast)?);
+                }
+
+                method.insert("set_pos".to_string());
+                result.push(draw_calls(tab, values, |values| {
+                    format!("draw.set_pos({})?;", values.join(", "))
+                }));
+            }
+            Stmt::Home(..) => {
+                method.insert("set_pos".to_string());
+                method.insert("home".to_string());
+                result.push(format!("{}draw.home()?;\n", set_tab(tab)));
+            }
+            Stmt::Window(..) | Stmt::Wrap(..) | Stmt::Fence(..) => {
+                let boundary = match stmt {
+                    Stmt::Window(..) => "Window",
+                    Stmt::Wrap(..) => "Wrap",
+                    _ => "Fence",
+                };
+
+                result.push(format!(
+                    "{}draw.set_boundary(crate::draw::Boundary::{});\n",
+                    set_tab(tab),
+                    boundary
+                ));
+            }
+            Stmt::ClearScreen(..) => {
+                method.insert("clear_screen".to_string());
+                result.push(format!("{}draw.clear_screen();\n", set_tab(tab)));
             }
             Stmt::Func(name, args, line) => {
                 let (name, end, len) = match name.as_ref() {
@@ -319,7 +564,7 @@ pub fn transpiler_stmt<'a>(
                                 *end,
                                 *len,
                             ));
-                        } else if args.len() > value {
+                        } else if args.len() < value {
                             return Err(match_err(
                                 file[*line].to_string(),
                                 *line,
@@ -340,9 +585,15 @@ pub fn transpiler_stmt<'a>(
                     }
                 };
 
+                let mut hoisted = String::new();
                 let mut args_result: Vec<String> = Vec::new();
                 for i in args.iter() {
-                    match transpiler_expr(i, *line, &file[*line], variable, method) {
+                    match transpiler_expr(i, *line, &file[*line], variable, method, ast) {
+                        Ok(Value::F(num)) if has_call(&num) => {
+                            let arg = format!("arg{}", args_result.len());
+                            hoisted.push_str(&format!("{}let {} = {};\n", set_tab(tab), arg, num));
+                            args_result.push(arg);
+                        }
                         Ok(Value::F(num)) => args_result.push(num),
                         _ => {
                             let (end, len) = get_end_len(i);
@@ -357,13 +608,76 @@ pub fn transpiler_stmt<'a>(
                     }
                 }
 
+                method.insert("call".to_string());
                 result.push(format!(
-                    "{}{}(draw, {});\n",
+                    "{}{}{}?;\n",
+                    hoisted,
                     set_tab(tab),
-                    name,
-                    args_result.join(", ")
+                    call(name, &args_result, ast)
                 ));
             }
+            Stmt::Output(expr, line) => {
+                let value = match (
+                    transpiler_expr(expr, *line, &file[*line], variable, method, ast)?,
+                    output,
+                ) {
+                    (Value::F(value), Some(false)) | (Value::B(value), Some(true)) => value,
+                    (Value::B(_), _) => {
+                        let (end, len) = get_end_len(expr);
+                        Err(match_err(
+                            file[*line].to_string(),
+                            *line,
+                            "UnexpectedBooleanType".to_string(),
+                            end,
+                            len,
+                        ))?
+                    }
+                    _ => {
+                        let (end, len) = get_end_len(expr);
+                        Err(match_err(
+                            file[*line].to_string(),
+                            *line,
+                            "UnexpectedNumberType".to_string(),
+                            end,
+                            len,
+                        ))?
+                    }
+                };
+
+                result.push(format!("{}return Ok(Some({}));\n", set_tab(tab), value));
+            }
+            Stmt::Stop(..) => match output {
+                Some(_) => result.push(format!("{}return Ok(None);\n", set_tab(tab))),
+                None => result.push(format!("{}return Ok(());\n", set_tab(tab))),
+            },
+            Stmt::Rerandom(expr, line) => {
+                let value = match transpiler_expr(expr, *line, &file[*line], variable, method, ast)?
+                {
+                    Value::F(num) => num,
+                    _ => {
+                        let (end, len) = get_end_len(expr);
+                        Err(match_err(
+                            file[*line].to_string(),
+                            *line,
+                            "UnexpectedBooleanType".to_string(),
+                            end,
+                            len,
+                        ))?
+                    }
+                };
+
+                method.insert("rerandom".to_string());
+                result.push(draw_call(tab, value, |value| {
+                    format!("draw.rerandom({})?;", value)
+                }));
+            }
+            Stmt::Local(assign, ..) => {
+                if let Assign::Var(name, ..) = assign.as_ref() {
+                    variable.remove(&var_name(name));
+                    method.insert("local".to_string());
+                    result.push(format!("{}draw.local({:?});\n", set_tab(tab), name));
+                }
+            }
             Stmt::Comments(comment, ..) => result.push(format!("{}// {}\n", set_tab(tab), comment)),
         }
     }
@@ -371,6 +685,61 @@ pub fn transpiler_stmt<'a>(
     Ok(result.join("\n"))
 }
 
+fn draw_call(tab: i32, value: String, call: impl Fn(&str) -> String) -> String {
+    if has_call(&value) {
+        format!(
+            "{}let value = {};\n{}{}\n",
+            set_tab(tab),
+            value,
+            set_tab(tab),
+            call("value")
+        )
+    } else {
+        format!("{}{}\n", set_tab(tab), call(&value))
+    }
+}
+
+fn draw_calls(tab: i32, values: Vec<String>, call: impl Fn(&[String]) -> String) -> String {
+    let mut result = String::new();
+    let values: Vec<String> = values
+        .into_iter()
+        .enumerate()
+        .map(|(idx, value)| {
+            if has_call(&value) {
+                result.push_str(&format!("{}let arg{} = {};\n", set_tab(tab), idx, value));
+                format!("arg{}", idx)
+            } else {
+                value
+            }
+        })
+        .collect();
+
+    format!("{}{}{}\n", result, set_tab(tab), call(&values))
+}
+
+fn transpiler_num<'a>(
+    expr: &Expr,
+    line: usize,
+    file: &[String],
+    variable: &HashMap<String, bool>,
+    method: &mut DrawMethod,
+    ast: &Function,
+) -> Result<String, TranspilerError<'a>> {
+    match transpiler_expr(expr, line, &file[line], variable, method, ast)? {
+        Value::F(num) => Ok(num),
+        _ => {
+            let (end, len) = get_end_len(expr);
+            Err(match_err(
+                file[line].to_string(),
+                line,
+                "UnexpectedBooleanType".to_string(),
+                end,
+                len,
+            ))
+        }
+    }
+}
+
 fn set_tab(tab: i32) -> String {
     (0..tab).map(|_| "\t").collect()
 }
@@ -0,0 +1,70 @@
+//! Renders animated GIFs and checks that each frame shows what was on the canvas at its step.
+use std::fs::{self, File};
+use std::path::Path;
+use std::process::Command;
+
+/// Renders `program` to a GIF and returns the RGBA pixels of every frame.
+fn frames(name: &str, program: &str) -> Vec<Vec<u8>> {
+    let dir = Path::new(env!("CARGO_TARGET_TMPDIR"));
+    let path = dir.join(format!("{name}.lg"));
+    let image = dir.join(format!("{name}.gif"));
+    fs::write(&path, program).expect("Failed to write the program");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rslogo"))
+        .args(["render", &path.display().to_string()])
+        .arg(&image)
+        .args(["--width", "200", "--height", "200"])
+        .output()
+        .expect("Failed to run rslogo");
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let mut options = gif::DecodeOptions::new();
+    options.set_color_output(gif::ColorOutput::RGBA);
+    let mut decoder = options
+        .read_info(File::open(image).expect("Failed to open the GIF"))
+        .expect("Failed to read the GIF");
+    let mut frames = Vec::new();
+    while let Some(frame) = decoder.read_next_frame().expect("Failed to read a frame") {
+        assert_eq!((frame.width, frame.height), (200, 200));
+        frames.push(frame.buffer.to_vec());
+    }
+    frames
+}
+
+fn pixel(frame: &[u8], (x, y): (usize, usize)) -> &[u8] {
+    &frame[(y * 200 + x) * 4..][..3]
+}
+
+#[test]
+fn frames_show_fills_beneath_outlines_and_clears() {
+    let program = r#"SETFILLCOLOR "2
+PENDOWN
+BEGINFILL
+REPEAT "4 [
+  FORWARD "40
+  TURN "90
+]
+ENDFILL
+CLEARSCREEN
+PENDOWN
+FORWARD "10
+"#;
+    let frames = frames("fill_then_clear", program);
+    let inside = (120, 80);
+    let black = [0, 0, 0];
+
+    let filled = frames
+        .iter()
+        .position(|frame| pixel(frame, inside) != black)
+        .expect("The fill was never shown");
+    // The fill goes beneath the outline, which must still show over it.
+    assert_ne!(
+        pixel(&frames[filled], (100, 80)),
+        pixel(&frames[filled], inside)
+    );
+    assert_eq!(pixel(frames.last().unwrap(), inside), black);
+}
@@ -0,0 +1,246 @@
+//! Runs the same program through the interpreter and through a transpiled crate, and checks
+//! that both back ends agree.
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+const CANVAS: [&str; 6] = ["--width", "200", "--height", "200", "--seed", "1"];
+
+fn rslogo(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_rslogo"))
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .args(args)
+        .output()
+        .expect("Failed to run rslogo")
+}
+
+fn write_program(name: &str, program: &str) -> PathBuf {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}.lg"));
+    fs::write(&path, program).expect("Failed to write the program");
+    path
+}
+
+fn assert_success(output: &Output) {
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+/// Renders `program` with the interpreter.
+fn interpret(name: &str, program: &str) -> String {
+    let program = write_program(name, program);
+    let image = program.with_extension("svg");
+
+    assert_success(&rslogo(
+        &[
+            &[
+                "render",
+                &program.display().to_string(),
+                &image.display().to_string(),
+            ],
+            &CANVAS[..],
+        ]
+        .concat(),
+    ));
+    fs::read_to_string(image).expect("Failed to read the interpreter image")
+}
+
+/// Transpiles `program`, then builds and runs the generated crate, which must build without
+/// warnings.
+fn transpile(name: &str, program: &str) -> String {
+    let program = write_program(name, program);
+    let name = format!("backends_{name}");
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join(&name);
+
+    assert_success(&rslogo(
+        &[
+            &["transpile", &program.display().to_string(), &name],
+            &CANVAS[..],
+        ]
+        .concat(),
+    ));
+    let output = Command::new(std::env::var_os("CARGO").unwrap_or("cargo".into()))
+        .current_dir(&root)
+        .env(
+            "CARGO_TARGET_DIR",
+            Path::new(env!("CARGO_TARGET_TMPDIR")).join("backends"),
+        )
+        .args(["run", "-q"])
+        .output()
+        .expect("Failed to run cargo");
+    let image = fs::read_to_string(root.join(format!("{name}.svg")));
+    fs::remove_dir_all(&root).expect("Failed to remove the generated crate");
+
+    assert_success(&output);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(!stderr.contains("warning"), "{stderr}");
+    image.expect("Failed to read the transpiled image")
+}
+
+#[test]
+fn procedures_have_their_own_variables() {
+    let program = r#"TO BRANCH "size
+  IF GT :size "5 [
+    FORWARD :size
+    TURN "-30
+    BRANCH / :size "2
+    TURN "60
+    BRANCH / :size "2
+    TURN "-30
+    BACK :size
+  ]
+END
+TO SHADOW "size
+  MAKE "size + :size "10
+  MAKE "step "3
+  FORWARD :size
+  FORWARD :step
+END
+MAKE "size "40
+MAKE "step "1
+PENDOWN
+BRANCH :size
+SHADOW :size
+FORWARD :size
+FORWARD :step
+"#;
+
+    assert_eq!(interpret("scope", program), transpile("scope", program));
+}
+
+#[test]
+fn procedures_share_global_variables() {
+    let program = r#"TO GROW "size
+  LOCAL "step
+  MAKE "step "3
+  FORWARD + :size :step
+  ADDASSIGN "total :size
+  MAKE "seen :total
+END
+MAKE "size "40
+MAKE "step "1
+MAKE "total "0
+PENDOWN
+GROW "10
+GROW "20
+FORWARD :size
+FORWARD :step
+FORWARD :seen
+"#;
+    let image = interpret("globals", program);
+
+    assert!(image.contains("d=\"M 100 64 L 100 24\""));
+    assert!(image.contains("d=\"M 100 24 L 100 23\""));
+    assert!(image.contains("d=\"M 100 23 L 100 -7\""));
+    assert_eq!(image, transpile("globals", program));
+}
+
+#[test]
+fn color_round_trips_through_setpencolor() {
+    let program = r##"SETPENRGB "18 "52 "86
+MAKE "brand COLOR
+SETPENCOLOR "1
+SETPENCOLOR "#ff8800
+MAKE "orange COLOR
+SETPENCOLOR :brand
+PENDOWN
+FORWARD "20
+SETPENCOLOR :orange
+TURN "90
+FORWARD "20
+SETFILLCOLOR :brand
+SETPENCOLOR FILLCOLOR
+TURN "90
+FORWARD "20
+"##;
+    let image = interpret("color", program);
+
+    assert_eq!(image.matches("stroke=\"#123456\"").count(), 2);
+    assert_eq!(image.matches("stroke=\"#ff8800\"").count(), 1);
+    assert_eq!(image, transpile("color", program));
+}
+
+#[test]
+fn only_the_rust_target_checks_transpiler_types() {
+    let program = write_program(
+        "check_target",
+        r#"TO F "b
+  IF :b [
+    FORWARD "10
+  ]
+END
+PENDOWN
+F EQ "1 "1
+"#,
+    );
+    let program = program.display().to_string();
+
+    assert_success(&rslogo(&["check", &program]));
+    assert!(!rslogo(&["check", &program, "--target", "rust"])
+        .status
+        .success());
+}
+
+#[test]
+fn labels_are_written_as_text() {
+    let program = r#"SETLABELHEIGHT "12
+LABEL "a<b
+TURN "90
+FORWARD "20
+LABEL "east
+"#;
+    let image = interpret("labels", program);
+
+    assert!(image.contains(
+        "<text x=\"100\" y=\"100\" font-family=\"sans-serif\" font-size=\"12\" \
+         fill=\"#ffffff\" transform=\"rotate(270 100 100)\">a&lt;b</text>"
+    ));
+    assert_eq!(image.matches("<text ").count(), 2);
+    assert_eq!(image, transpile("labels", program));
+}
+
+#[test]
+fn heading_stays_within_a_full_turn() {
+    let program = r#"PENDOWN
+REPEAT "4 [
+  TURN "90
+]
+FORWARD + "10 HEADING
+TURN "-90
+FORWARD / HEADING "27
+SETHEADING "450
+FORWARD / HEADING "9
+"#;
+    let image = interpret("heading", program);
+
+    assert!(image.contains("d=\"M 100 100 L 100 90\""));
+    assert!(image.contains("d=\"M 100 90 L 90 90\""));
+    assert!(image.contains("d=\"M 90 90 L 100 90\""));
+    assert_eq!(image, transpile("heading", program));
+}
+
+#[test]
+fn uncalled_procedures_are_left_out() {
+    let program = r#"TO UNUSED
+  LABEL "never
+END
+TO SQUARE
+  REPEAT "4 [
+    FORWARD "10
+    TURN "90
+  ]
+END
+TO PATTERN
+  SQUARE
+  SETHEADING "45
+END
+PENDOWN
+PATTERN
+"#;
+    assert_eq!(
+        interpret("uncalled", program),
+        transpile("uncalled", program)
+    );
+}
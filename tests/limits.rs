@@ -0,0 +1,72 @@
+//! Runs programs that recurse without end and checks they stop with a diagnostic rather than
+//! overflowing the stack.
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// Renders `program` and returns what it printed to stderr, expecting it to fail.
+fn render_err(name: &str, program: &str, args: &[&str]) -> String {
+    let path = Path::new(env!("CARGO_TARGET_TMPDIR")).join(format!("{name}.lg"));
+    fs::write(&path, program).expect("Failed to write the program");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_rslogo"))
+        .args(["render", &path.display().to_string(), "-"])
+        .args(["--width", "100", "--height", "100"])
+        .args(args)
+        .output()
+        .expect("Failed to run rslogo");
+    assert!(!output.status.success());
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn recursion_through_nested_blocks_hits_the_depth_limit() {
+    let program = r#"TO T "n
+  IF GT :n "0 [
+    REPEAT "1 [
+      IF EQ "1 "1 [
+        T - :n "1
+      ]
+    ]
+  ]
+END
+T "2000
+"#;
+
+    assert!(render_err("nested_blocks", program, &[]).contains("Recursion limit exceeded"));
+}
+
+#[test]
+fn recursion_inside_expressions_hits_the_depth_limit() {
+    let program = r#"TO F "n
+  IF GT :n "0 [
+    REPEAT "1 [
+      WHILE EQ "1 "0 [
+        FORWARD "1
+      ]
+      IF EQ "1 "1 [
+        OUTPUT + "1 F - :n "1
+      ]
+    ]
+  ]
+  OUTPUT "0
+END
+FORWARD F "2000
+"#;
+
+    assert!(render_err("nested_expressions", program, &[]).contains("Recursion limit exceeded"));
+}
+
+#[test]
+fn huge_depth_limits_are_bounded_by_the_stack() {
+    let program = r#"TO T "n
+  IF GT :n "0 [
+    T - :n "1
+  ]
+END
+T "100000000
+"#;
+    let stderr = render_err("huge_depth", program, &["--max-depth", "100000000"]);
+
+    assert!(stderr.contains("Recursion limit exceeded"), "{stderr}");
+}